@@ -0,0 +1,158 @@
+// Derive macro for the mikrotik::resource::Resource trait.
+//
+//     #[derive(Default, Resource)]
+//     #[resource(menu = "/ip/firewall/address-list")]
+//     pub struct AddressListEntry {
+//         #[resource(id)]
+//         pub id: String,
+//         pub list: String,
+//         pub address: String,
+//         #[resource(read_only)]
+//         pub dynamic: bool,
+//         #[resource(rename = "creation-time", read_only)]
+//         pub created: Option<String>,
+//     }
+//
+// Every field maps to the attribute named like it with '_' replaced by '-', unless
+// renamed. The 'id' field maps to '.id' and, like 'read_only' ones, is never sent
+// when adding or setting the item. Attributes missing from a reply leave the field
+// with its default value.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(Resource, attributes(resource))]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+struct Field {
+    ident: syn::Ident,
+    key: String,
+    id: bool,
+    read_only: bool,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+    let menu = menu(input)?;
+
+    let fields = match input.data {
+        Data::Struct(DataStruct { fields: Fields::Named(ref fields), .. }) => &fields.named,
+        _ => {
+            let msg = "Resource can only be derived for structs with named fields";
+            return Err(syn::Error::new_spanned(input, msg));
+        }
+    };
+
+    let fields = fields.iter().map(field).collect::<Result<Vec<_>, _>>()?;
+
+    let parsed = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let key = &f.key;
+        quote! { #ident: ::mikrotik::resource::parse_field(sentence, #key)? }
+    });
+
+    let written = fields.iter().filter(|f| !f.id && !f.read_only).map(|f| {
+        let ident = &f.ident;
+        let key = &f.key;
+        quote! {
+            if let Some(value) = ::mikrotik::resource::Field::to_value(&self.#ident) {
+                attributes.push((#key.to_string(), value));
+            }
+        }
+    });
+
+    let id = match fields.iter().find(|f| f.id) {
+        Some(f) => {
+            let ident = &f.ident;
+            quote! {
+                fn id(&self) -> Option<&str> {
+                    Some(self.#ident.as_str())
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::mikrotik::resource::Resource for #name #ty_generics #where_clause {
+            fn menu() -> &'static str {
+                #menu
+            }
+
+            fn from_sentence(sentence: &::mikrotik::sentence::Sentence)
+                             -> Result<Self, ::mikrotik::errors::MikrotikError> {
+                Ok(#name {
+                    #(#parsed,)*
+                })
+            }
+
+            fn attributes(&self) -> Vec<(String, String)> {
+                let mut attributes = Vec::new();
+                #(#written)*
+                attributes
+            }
+
+            #id
+        }
+    })
+}
+
+// Returns the value of the #[resource(menu = "...")] attribute of the struct.
+fn menu(input: &DeriveInput) -> Result<LitStr, syn::Error> {
+    let mut menu = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("resource")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("menu") {
+                menu = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown resource attribute"))
+            }
+        })?;
+    }
+
+    menu.ok_or_else(|| syn::Error::new_spanned(input, "missing #[resource(menu = \"...\")]"))
+}
+
+fn field(field: &syn::Field) -> Result<Field, syn::Error> {
+    let ident = field.ident.clone().unwrap();
+    let name = ident.to_string();
+    let mut parsed = Field {
+        key: name.trim_start_matches("r#").replace('_', "-"),
+        ident,
+        id: false,
+        read_only: false,
+    };
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("resource")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                parsed.id = true;
+                parsed.key = String::from(".id");
+            } else if meta.path.is_ident("read_only") {
+                parsed.read_only = true;
+            } else if meta.path.is_ident("rename") {
+                parsed.key = meta.value()?.parse::<LitStr>()?.value();
+            } else {
+                return Err(meta.error("unknown resource attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(parsed)
+}
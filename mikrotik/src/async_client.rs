@@ -0,0 +1,502 @@
+use crate::client::{self, READ_BUFFER_SIZE};
+use crate::codec;
+use crate::encoding::Encoding;
+use crate::errors;
+use crate::executor::{Executor, Parser};
+use crate::models::TrafficSample;
+use crate::listen::{self, Event};
+use crate::sentence::{Reply, Sentence};
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncWriteExt, ReadBuf};
+use tokio::net::{self, TcpStream, ToSocketAddrs};
+
+use std::future::{self, Future};
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+// AsyncClient is the tokio based counterpart of client::Client.
+//
+// It exposes the same operations as the blocking client, sharing the word encoding,
+// reply parsing and error types with it, but every call that talks to the router
+// returns a future instead of blocking the calling thread.
+pub struct AsyncClient {
+    sock_addr: SocketAddr,
+    stream: TcpStream,
+    decoder: codec::Decoder,
+    next_tag: usize,
+    encoding: Encoding,
+}
+
+impl AsyncClient {
+    // Connects to the api service at 'addr', trying every address it resolves to in
+    // order. See Client::connect.
+    pub async fn connect<A: ToSocketAddrs>(addr: A)
+                                           -> Result<AsyncClient, errors::CreateClientError> {
+        let mut last_err = None;
+
+        for sock_addr in net::lookup_host(addr).await? {
+            match TcpStream::connect(sock_addr).await {
+                Ok(stream) => {
+                    return Ok(AsyncClient {
+                        sock_addr,
+                        stream,
+                        decoder: codec::Decoder::new(),
+                        next_tag: 0,
+                        encoding: Encoding::default(),
+                    })
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(client::no_address_error).into())
+    }
+
+    // Returns the address of the router the client is connected to.
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.sock_addr
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    // See Client::set_encoding.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    // See Client::info.
+    pub fn info(&self) -> String {
+        format!("Router OS Client connected to {}", self.sock_addr)
+    }
+
+    // Polls the stream until the decoder yields a complete sentence.
+    fn poll_read_words(&mut self, cx: &mut Context<'_>) -> Poll<Result<Vec<Vec<u8>>, io::Error>> {
+        let mut buffer = [0; READ_BUFFER_SIZE];
+
+        loop {
+            if let Some(sentence) = self.decoder.decode_sentence()? {
+                return Poll::Ready(Ok(sentence));
+            }
+
+            let mut read_buf = ReadBuf::new(&mut buffer);
+            ready!(Pin::new(&mut self.stream).poll_read(cx, &mut read_buf))?;
+
+            if read_buf.filled().is_empty() {
+                let err = io::Error::new(io::ErrorKind::ConnectionReset,
+                                         "error reading: connection closed by remote end");
+                return Poll::Ready(Err(err));
+            }
+
+            self.decoder.feed(read_buf.filled());
+        }
+    }
+
+    fn poll_read_sentence(&mut self, cx: &mut Context<'_>) -> Poll<Result<Vec<String>, io::Error>> {
+        let words = ready!(self.poll_read_words(cx))?;
+
+        Poll::Ready(Ok(self.encoding.decode_words(&words)))
+    }
+
+    // See Client::write_words.
+    pub async fn write_words(&mut self, words: &[Vec<u8>]) -> Result<(), io::Error> {
+        let mut buffer: Vec<u8> = Vec::new();
+        codec::encode_sentence(words, &mut buffer)?;
+
+        self.stream.write_all(&buffer).await
+    }
+
+    pub async fn write_sentence(&mut self, words: &[String]) -> Result<(), io::Error> {
+        self.write_words(&self.encoding.encode_words(words)).await
+    }
+
+    // See Client::read_words.
+    pub async fn read_words(&mut self) -> Result<Vec<Vec<u8>>, io::Error> {
+        future::poll_fn(|cx| self.poll_read_words(cx)).await
+    }
+
+    pub async fn read_sentence(&mut self) -> Result<Vec<String>, io::Error> {
+        future::poll_fn(|cx| self.poll_read_sentence(cx)).await
+    }
+
+    // See Client::read_all_sentences.
+    pub async fn read_all_sentences(&mut self) -> Result<Vec<Sentence>, errors::MikrotikError> {
+        let mut sentences: Vec<Sentence> = Vec::new();
+        let mut trap = None;
+
+        loop {
+            let sentence = self.read_sentence().await?;
+
+            if sentence.is_empty() {
+                continue;
+            }
+
+            let sentence = Sentence::parse(&sentence)?;
+            match sentence.error() {
+                Some(e @ errors::MikrotikError::Fatal(_)) => return Err(e),
+                Some(e) => {
+                    trap.get_or_insert(e);
+                    continue;
+                }
+                None => {}
+            }
+
+            let is_done = sentence.reply == Reply::Done;
+            sentences.push(sentence);
+
+            if is_done {
+                break;
+            }
+        }
+
+        match trap {
+            Some(e) => Err(e),
+            None => Ok(sentences),
+        }
+    }
+
+    pub async fn execute(&mut self,
+                         sentence: &[String])
+                         -> Result<Vec<Sentence>, errors::MikrotikError> {
+        if sentence.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.write_sentence(sentence).await?;
+
+        self.read_all_sentences().await
+    }
+
+    fn next_tag(&mut self) -> String {
+        self.next_tag += 1;
+        self.next_tag.to_string()
+    }
+
+    // Sends a command that keeps replying until it is cancelled and returns a Stream
+    // over each of its '!re' replies. See Client::listen.
+    //
+    // Futures can not be awaited on drop, so unlike the blocking listener the command
+    // is not cancelled automatically: call AsyncListener::cancel before reusing the
+    // client.
+    pub async fn listen(&mut self,
+                        sentence: &[String])
+                        -> Result<AsyncListener<'_>, errors::MikrotikError> {
+        let tag = self.next_tag();
+        self.write_sentence(&client::tag_sentence(sentence, &tag)).await?;
+
+        Ok(AsyncListener {
+            client: self,
+            tag,
+            done: false,
+        })
+    }
+
+    // Streams the traffic of 'interface' every second. See Client::monitor_traffic.
+    //
+    // As with AsyncClient::listen, call AsyncTrafficMonitor::cancel before reusing the
+    // client.
+    pub async fn monitor_traffic(&mut self,
+                                 interface: &str)
+                                 -> Result<AsyncTrafficMonitor<'_>, errors::MikrotikError> {
+        let listener = self.listen(&client::monitor_traffic_sentence(interface)).await?;
+
+        Ok(AsyncTrafficMonitor { listener })
+    }
+
+    // Logs in with the post 6.43 method, falling back to the legacy one on older
+    // routers. See Client::login.
+    pub async fn login(&mut self, username: &str, pwd: &str) -> Result<(), errors::MikrotikError> {
+        let response = self.execute(&client::login_request(username, pwd)).await?;
+
+        if let Some(challenge) = client::login_challenge(&response)? {
+            self.execute(&client::legacy_login_request(username, pwd, &challenge)).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn login_legacy(&mut self,
+                              username: &str,
+                              pwd: &str)
+                              -> Result<(), errors::MikrotikError> {
+        let response = self.execute(&["/login".to_string()]).await?;
+
+        match client::login_challenge(&response)? {
+            Some(challenge) => {
+                self.execute(&client::legacy_login_request(username, pwd, &challenge)).await?;
+                Ok(())
+            }
+            None => {
+                let msg = String::from("no challenge in reply to /login");
+                Err(errors::MikrotikError::UnexpectedReply(msg))
+            }
+        }
+    }
+
+}
+
+impl<'a> Executor for &'a mut AsyncClient {
+    type Output<R> = AsyncRequest<'a, R>;
+
+    fn run<R>(self,
+              sentence: Result<Vec<String>, errors::MikrotikError>,
+              parse: Parser<R>)
+              -> AsyncRequest<'a, R> {
+        AsyncRequest {
+            response: Box::pin(async move { self.execute(&sentence?).await }),
+            parse,
+        }
+    }
+}
+
+type Response<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<Sentence>, errors::MikrotikError>> + Send + 'a>>;
+
+// AsyncRequest is the future returned by the Executor methods of AsyncClient. It
+// resolves to the result of the command once all its replies are read.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AsyncRequest<'a, R> {
+    response: Response<'a>,
+    parse: Parser<R>,
+}
+
+impl<R> Future for AsyncRequest<'_, R> {
+    type Output = Result<R, errors::MikrotikError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let response = ready!(this.response.as_mut().poll(cx))?;
+
+        Poll::Ready((this.parse)(&response))
+    }
+}
+
+// AsyncListener streams the replies of a command started with AsyncClient::listen.
+//
+// It yields the same items as listen::Listener, either through the Stream trait or
+// the 'next' method.
+pub struct AsyncListener<'a> {
+    client: &'a mut AsyncClient,
+    tag: String,
+    done: bool,
+}
+
+impl AsyncListener<'_> {
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub async fn next(&mut self) -> Option<Result<Sentence, errors::MikrotikError>> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    // Stops the command by sending '/cancel' with its tag and discards every reply
+    // still in flight for it.
+    pub async fn cancel(self) -> Result<(), errors::MikrotikError> {
+        if self.done {
+            return Ok(());
+        }
+
+        let cancel_tag = self.client.next_tag();
+        let request = vec!["/cancel".to_string(), format!("=tag={}", self.tag)];
+        self.client.write_sentence(&client::tag_sentence(&request, &cancel_tag)).await?;
+
+        let mut done = false;
+        let mut cancel_error = None;
+        let mut confirmed = false;
+
+        while !done || !confirmed {
+            let sentence = self.client.read_sentence().await?;
+            if sentence.is_empty() {
+                continue;
+            }
+
+            let sentence = Sentence::parse(&sentence)?;
+
+            match sentence.tag.as_deref() {
+                Some(t) if t == self.tag => done = sentence.reply == Reply::Done,
+                Some(t) if t == cancel_tag => {
+                    match sentence.error() {
+                        Some(e) => cancel_error = Some(e),
+                        None => confirmed = sentence.reply == Reply::Done,
+                    }
+                }
+                _ => {
+                    if let Some(e @ errors::MikrotikError::Fatal(_)) = sentence.error() {
+                        return Err(e);
+                    }
+                }
+            }
+
+            // The router refused to cancel, so the command will not be interrupted.
+            if confirmed && cancel_error.is_some() {
+                break;
+            }
+        }
+
+        match cancel_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Stream for AsyncListener<'_> {
+    type Item = Result<Sentence, errors::MikrotikError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.done {
+            let sentence = match ready!(this.client.poll_read_sentence(cx)) {
+                Ok(s) => s,
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+            };
+
+            match listen::listen_event(&sentence, &this.tag) {
+                Event::Reply(sentence) => return Poll::Ready(Some(Ok(sentence))),
+                Event::Error(e) => {
+                    this.done = listen::is_terminal(&e);
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Event::Done => this.done = true,
+                Event::Ignore => {}
+            }
+        }
+
+        Poll::Ready(None)
+    }
+}
+
+// AsyncTrafficMonitor streams the samples of a command started with
+// AsyncClient::monitor_traffic.
+pub struct AsyncTrafficMonitor<'a> {
+    listener: AsyncListener<'a>,
+}
+
+impl AsyncTrafficMonitor<'_> {
+    pub async fn next(&mut self) -> Option<Result<TrafficSample, errors::MikrotikError>> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    // Stops monitoring. See AsyncListener::cancel.
+    pub async fn cancel(self) -> Result<(), errors::MikrotikError> {
+        self.listener.cancel().await
+    }
+}
+
+impl Stream for AsyncTrafficMonitor<'_> {
+    type Item = Result<TrafficSample, errors::MikrotikError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match ready!(Pin::new(&mut this.listener).poll_next(cx)) {
+            Some(r) => Poll::Ready(Some(r.and_then(|s| TrafficSample::from_sentence(&s)))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MikrotikError;
+    use crate::test_utils::{mock_router, words};
+
+    #[tokio::test]
+    async fn test_login_and_address_list() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/login", "=name=admin", "=password="],
+                        &[words(&["!done", "=ret=ebddd18303a54111e2dea05a92ab46b4"])]);
+            conn.expect(&["/login",
+                          "=name=admin",
+                          "=response=001ea726ed53ae38520c8334f82d44c9f2"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/ip/firewall/address-list/print"],
+                        &[words(&["!re", "=.id=*1", "=list=blocked", "=address=10.0.0.1"]),
+                          words(&["!re", "=.id=*2", "=list=blocked", "=address=10.0.0.2"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = AsyncClient::connect(("127.0.0.1", port)).await.unwrap();
+        client.login("admin", "").await.unwrap();
+
+        let addresses = client.get_address_list().await.unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[0].id, "*1");
+        assert_eq!(addresses[1].address.to_string(), "10.0.0.2");
+    }
+
+    #[tokio::test]
+    async fn test_trap_is_returned_as_error() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/queue/simple/print"],
+                        &[words(&["!trap", "=category=0", "=message=no such command"]),
+                          words(&["!done"])]);
+            conn.expect(&["/system/identity/print"],
+                        &[words(&["!re", "=name=router"]), words(&["!done"])]);
+        });
+
+        let mut client = AsyncClient::connect(("127.0.0.1", port)).await.unwrap();
+
+        match client.get_queue_list().await {
+            Err(MikrotikError::Trap { category, msg }) => {
+                assert_eq!(category, 0);
+                assert_eq!(msg, "no such command");
+            }
+            _ => panic!("expected a trap error"),
+        }
+
+        let request = vec!["/system/identity/print".to_string()];
+        let response = client.execute(&request).await.unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+    }
+
+    #[tokio::test]
+    async fn test_listen_stream_and_cancel() {
+        let port = listen::tests::streaming_router();
+        let mut client = AsyncClient::connect(("127.0.0.1", port)).await.unwrap();
+
+        {
+            let request = vec!["/interface/monitor-traffic".to_string()];
+            let mut listener = client.listen(&request).await.unwrap();
+
+            let first = listener.next().await.unwrap().unwrap();
+            assert_eq!(first.get("rx-bits-per-second"), Some("100"));
+
+            listener.cancel().await.unwrap();
+        }
+
+        let request = vec!["/system/identity/print".to_string()];
+        let response = client.execute(&request).await.unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+        assert_eq!(response[1].reply, Reply::Done);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_traffic() {
+        let port = listen::tests::streaming_router();
+        let mut client = AsyncClient::connect(("127.0.0.1", port)).await.unwrap();
+
+        {
+            let mut monitor = client.monitor_traffic("ether1").await.unwrap();
+
+            let sample = monitor.next().await.unwrap().unwrap();
+            assert_eq!(sample.rx_bits_per_second.0, 100);
+
+            monitor.cancel().await.unwrap();
+        }
+
+        let request = vec!["/system/identity/print".to_string()];
+        let response = client.execute(&request).await.unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::client::{self, Client};
+use crate::encoding::Encoding;
+use crate::errors::CreateClientError;
+use crate::transport::Transport;
+#[cfg(feature = "tls")]
+use crate::tls::{self, TlsOptions};
+
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+// ClientBuilder connects a Client with limits on how long it waits for the router.
+//
+// Every timeout is disabled by default, which makes Client::connect wait forever on
+// a router that accepts the connection but never answers.
+//
+//     let client = ClientBuilder::new()
+//         .connect_timeout(Duration::from_secs(5))
+//         .command_timeout(Duration::from_secs(30))
+//         .connect(("192.168.88.1", DEFAULT_PORT))?;
+//
+#[derive(Clone, Debug, Default)]
+pub struct ClientBuilder {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    command_timeout: Option<Duration>,
+    encoding: Encoding,
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    // Gives up on each address the router resolves to after 'timeout', returning
+    // CreateClientError::Timeout if none of them answered.
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    // Limits how long a single read from the router can block.
+    pub fn read_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    // Limits how long a single write to the router can block.
+    pub fn write_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    // Limits how long the router can take to answer a whole command, however
+    // steadily it sends replies.
+    //
+    // Commands started with Client::listen run until they are cancelled, so only the
+    // read timeout applies to them.
+    pub fn command_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.command_timeout = Some(timeout);
+        self
+    }
+
+    // Sets the encoding of the text sent to and received from the router. See
+    // Client::set_encoding.
+    pub fn encoding(mut self, encoding: Encoding) -> ClientBuilder {
+        self.encoding = encoding;
+        self
+    }
+
+    // Connects to the api service at 'addr'. See Client::connect.
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> Result<Client, CreateClientError> {
+        let (stream, sock_addr) = self.connect_tcp(addr)?;
+
+        Ok(self.client(sock_addr, Transport::Plain(stream)))
+    }
+
+    // Connects to the api-ssl service at 'addr'. See Client::connect_tls.
+    //
+    // The read and write timeouts also apply to the TLS handshake.
+    #[cfg(feature = "tls")]
+    pub fn connect_tls(&self,
+                       addr: (&str, u16),
+                       options: &TlsOptions)
+                       -> Result<Client, CreateClientError> {
+        let (stream, sock_addr) = self.connect_tcp(addr)?;
+        let stream = tls::connect(stream, addr.0, options)?;
+
+        Ok(self.client(sock_addr, Transport::Tls(stream)))
+    }
+
+    fn connect_tcp<A: ToSocketAddrs>(&self,
+                                     addr: A)
+                                     -> Result<(TcpStream, SocketAddr), CreateClientError> {
+        let (stream, sock_addr) = client::connect_tcp(addr, self.connect_timeout)?;
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+
+        Ok((stream, sock_addr))
+    }
+
+    fn client(&self, sock_addr: SocketAddr, stream: Transport) -> Client {
+        Client::with_transport(sock_addr,
+                               stream,
+                               self.read_timeout,
+                               self.command_timeout,
+                               self.encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec;
+    use crate::errors::MikrotikError;
+    use crate::executor::Executor;
+    use crate::test_utils::{mock_router, words};
+
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Mock router that reads one command and never answers it.
+    fn hung_router() -> u16 {
+        mock_router(|conn| {
+            conn.read();
+            let _ = conn.stream.read(&mut [0; 1]);
+        })
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let port = hung_router();
+        let mut client = ClientBuilder::new()
+            .read_timeout(Duration::from_millis(100))
+            .connect(("127.0.0.1", port))
+            .unwrap();
+
+        match client.get_address_list() {
+            Err(MikrotikError::Timeout) => {}
+            _ => panic!("expected a timeout error"),
+        }
+    }
+
+    #[test]
+    fn test_command_timeout() {
+        // Keeps sending replies well within the read timeout, but never '!done'.
+        let port = mock_router(|conn| {
+            conn.read();
+
+            let mut out = Vec::new();
+            codec::encode_sentence(&["!re", "=.id=*1", "=list=blocked"], &mut out).unwrap();
+
+            while conn.stream.write_all(&out).is_ok() {
+                thread::sleep(Duration::from_millis(20));
+            }
+        });
+        let mut client = ClientBuilder::new()
+            .read_timeout(Duration::from_secs(5))
+            .command_timeout(Duration::from_millis(200))
+            .connect(("127.0.0.1", port))
+            .unwrap();
+
+        match client.get_address_list() {
+            Err(MikrotikError::Timeout) => {}
+            _ => panic!("expected a timeout error"),
+        }
+    }
+
+    #[test]
+    fn test_command_within_timeout() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/address-list/print"],
+                        &[words(&["!re", "=.id=*1", "=list=blocked", "=address=10.0.0.1"]),
+                          words(&["!done"])]);
+        });
+        let mut client = ClientBuilder::new()
+            .command_timeout(Duration::from_secs(5))
+            .connect(("127.0.0.1", port))
+            .unwrap();
+
+        let addresses = client.get_address_list().unwrap();
+        assert_eq!(addresses[0].address.to_string(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_refused_connection_is_not_a_timeout() {
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let result = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(1))
+            .connect(addr);
+
+        match result {
+            Err(CreateClientError::IoError(_)) => {}
+            _ => panic!("expected an io error"),
+        }
+    }
+}
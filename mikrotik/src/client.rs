@@ -1,63 +1,123 @@
 extern crate crypto;
 
-use utils;
-use errors;
-use models;
+use crate::utils;
+use crate::builder::ClientBuilder;
+use crate::codec;
+use crate::encoding::Encoding;
+use crate::errors;
+use crate::executor::{Executor, Parser};
+use crate::listen::{Listener, TrafficMonitor};
+use crate::query::Query;
+use crate::sentence::{Reply, Sentence};
+use crate::transport::Transport;
+#[cfg(feature = "tls")]
+use crate::tls::TlsOptions;
 
 use self::crypto::md5::Md5;
 use self::crypto::digest::Digest;
 
-use std::net::{SocketAddrV4, TcpStream, Ipv4Addr};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
 use std::io::{self, Read, Write};
-use std::str::FromStr;
-use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 pub const DEFAULT_PORT: u16 = 8728;
+pub const DEFAULT_TLS_PORT: u16 = 8729;
+
+pub(crate) const READ_BUFFER_SIZE: usize = 4096;
 
 pub struct Client {
-    sock_addr: SocketAddrV4,
-    stream: TcpStream,
+    sock_addr: SocketAddr,
+    stream: Transport,
+    decoder: codec::Decoder,
+    next_tag: usize,
+    read_timeout: Option<Duration>,
+    command_timeout: Option<Duration>,
+    encoding: Encoding,
 }
 
 impl Client {
-    pub fn connect(ip: &str, port: &str) -> Result<Client, errors::CreateClientError> {
-        let ip = try!(Ipv4Addr::from_str(ip));
-        let port = port.parse::<u16>().unwrap_or(DEFAULT_PORT);
-        let sock_addr = SocketAddrV4::new(ip, port);
+    // Connects to the api service at 'addr', which can be anything implementing
+    // ToSocketAddrs: an (ip, port) or (hostname, port) tuple, or a "host:port" string
+    // such as "router.lan:8728" or "[fe80::1]:8728".
+    //
+    // Every address 'addr' resolves to is tried in order until one accepts the
+    // connection.
+    //
+    // The client waits for the router forever; use ClientBuilder to set timeouts.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Client, errors::CreateClientError> {
+        ClientBuilder::new().connect(addr)
+    }
 
-        println!("Connecting to {}:{}...", ip, port);
-        let stream = try!(TcpStream::connect(sock_addr));
+    // Connects to the api-ssl service at 'addr', a (host, port) tuple such as
+    // ("192.168.88.1", DEFAULT_TLS_PORT). The certificate of the router is verified
+    // against 'host', whether an IP address or a name; see TlsOptions.
+    #[cfg(feature = "tls")]
+    pub fn connect_tls(addr: (&str, u16),
+                       options: &TlsOptions)
+                       -> Result<Client, errors::CreateClientError> {
+        ClientBuilder::new().connect_tls(addr, options)
+    }
 
-        Ok(Client {
-            sock_addr: sock_addr,
-            stream: stream,
-        })
+    // Returns a ClientBuilder to connect with timeouts.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
     }
 
-    pub fn print(&self) {
-        println!("Router OS Client.");
-        println!("Connecting to server {}:{}",
-                 self.sock_addr.ip(),
-                 self.sock_addr.port());
+    pub(crate) fn with_transport(sock_addr: SocketAddr,
+                                 stream: Transport,
+                                 read_timeout: Option<Duration>,
+                                 command_timeout: Option<Duration>,
+                                 encoding: Encoding)
+                                 -> Client {
+        Client {
+            sock_addr,
+            stream,
+            decoder: codec::Decoder::new(),
+            next_tag: 0,
+            read_timeout,
+            command_timeout,
+            encoding,
+        }
     }
 
-    fn read_str(&mut self, total_bytes: usize) -> Result<Vec<u8>, io::Error> {
-        let mut buffer: Vec<u8> = vec![0; total_bytes];
-        let mut bytes_read = 0 as usize;
+    // Splits the client into a reading and a writing handle to the same connection,
+    // along with the decoder holding any bytes already received from the router and
+    // the encoding of the text sent over it.
+    //
+    // The reading handle waits for replies forever, since an idle connection is not
+    // an error for it.
+    pub(crate) fn split(self)
+                        -> Result<(TcpStream, TcpStream, codec::Decoder, Encoding), io::Error> {
+        let reader = self.stream.try_clone()?;
+        let writer = self.stream.try_clone()?;
+        reader.set_read_timeout(None)?;
+
+        Ok((reader, writer, self.decoder, self.encoding))
+    }
 
-        while bytes_read < total_bytes {
-            let x = try!(self.stream.read(&mut buffer[bytes_read..]));
+    // Returns the address of the router the client is connected to.
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.sock_addr
+    }
 
-            if x == 0 {
-                let err = io::Error::new(io::ErrorKind::ConnectionReset,
-                                         "error reading: connection closed by remote end");
-                return Err(err);
-            }
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
 
-            bytes_read += x;
-        }
+    // Sets the encoding used to convert the text of every word sent to or received
+    // from the router. UTF-8 is used unless set otherwise.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    // Returns a line describing the client and the router it is connected to.
+    pub fn info(&self) -> String {
+        format!("Router OS Client connected to {}", self.sock_addr)
+    }
 
-        Ok(buffer)
+    #[deprecated(note = "print Client::info instead")]
+    pub fn print(&self) {
+        println!("{}", self.info());
     }
 
     fn write_str(&mut self, buffer: &[u8]) -> Result<(), io::Error> {
@@ -65,7 +125,7 @@ impl Client {
         let mut bytes_written = 0;
 
         while bytes_written < total_bytes {
-            let x = try!(self.stream.write(&buffer[bytes_written..]));
+            let x = self.stream.write(&buffer[bytes_written..])?;
 
             if x == 0 {
                 let err = io::Error::new(io::ErrorKind::ConnectionReset,
@@ -79,352 +139,1217 @@ impl Client {
         Ok(())
     }
 
-    fn read_len(&mut self) -> Result<usize, io::Error> {
-        let mut c = try!(self.read_str(1))[0] as usize;
-
-        if c & 0x80 == 0x00 {
-            return Ok(c);
-        } else if c & 0xC0 == 0x80 {
-            c &= !0xC0;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-        } else if c & 0xE0 == 0xC0 {
-            c &= !0xE0;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-        } else if c & 0xF0 == 0xE0 {
-            c &= !0xF0;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-        } else if c & 0xF8 == 0xF0 {
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
-            c << 8;
-            c += try!(self.read_str(1))[0] as usize;
+    // Sends a sentence made of raw words, without converting them with the encoding
+    // of the client.
+    pub fn write_words(&mut self, words: &[Vec<u8>]) -> Result<(), io::Error> {
+        let mut buffer: Vec<u8> = Vec::new();
+        codec::encode_sentence(words, &mut buffer)?;
+
+        self.write_str(&buffer)
+    }
+
+    pub fn write_sentence(&mut self, words: &[String]) -> Result<(), io::Error> {
+        self.write_words(&self.encoding.encode_words(words))
+    }
+
+    // Reads the next sentence as raw words, without converting them with the encoding
+    // of the client.
+    pub fn read_words(&mut self) -> Result<Vec<Vec<u8>>, io::Error> {
+        read_words(&mut self.stream, &mut self.decoder)
+    }
+
+    pub fn read_sentence(&mut self) -> Result<Vec<String>, io::Error> {
+        let words = self.read_words()?;
+
+        Ok(self.encoding.decode_words(&words))
+    }
+
+    // Reads every reply to the last command up to '!done'. A '!trap' reply is returned
+    // as an error once the '!done' that still follows it is read, so the connection is
+    // left ready for the next command. A '!fatal' one is returned right away, as the
+    // router closes the connection after it.
+    //
+    // With a command timeout set, MikrotikError::Timeout is returned once it elapses
+    // even if the router keeps sending replies.
+    pub fn read_all_sentences(&mut self) -> Result<Vec<Sentence>, errors::MikrotikError> {
+        let deadline = match self.command_timeout {
+            Some(timeout) => Instant::now() + timeout,
+            None => return self.read_replies(None),
+        };
+
+        let result = self.read_replies(Some(deadline));
+        self.stream.socket().set_read_timeout(self.read_timeout)?;
+
+        result
+    }
+
+    fn read_replies(&mut self,
+                    deadline: Option<Instant>)
+                    -> Result<Vec<Sentence>, errors::MikrotikError> {
+        let mut sentences: Vec<Sentence> = Vec::new();
+        let mut trap = None;
+
+        loop {
+            let sentence = match deadline {
+                Some(deadline) => {
+                    let mut reader = DeadlineReader {
+                        stream: &mut self.stream,
+                        read_timeout: self.read_timeout,
+                        deadline,
+                    };
+                    let words = read_words(&mut reader, &mut self.decoder)?;
+                    self.encoding.decode_words(&words)
+                }
+                None => self.read_sentence()?,
+            };
+
+            if sentence.is_empty() {
+                continue;
+            }
+
+            let sentence = Sentence::parse(&sentence)?;
+            match sentence.error() {
+                Some(e @ errors::MikrotikError::Fatal(_)) => return Err(e),
+                Some(e) => {
+                    trap.get_or_insert(e);
+                    continue;
+                }
+                None => {}
+            }
+
+            let is_done = sentence.reply == Reply::Done;
+            sentences.push(sentence);
+
+            if is_done {
+                break;
+            }
         }
 
-        Ok(c)
-    }
-
-    fn write_len(&mut self, mut len: usize) -> Result<(), io::Error> {
-        if len < 0x80 {
-            try!(self.write_str(&[len as u8]));
-        } else if len < 0x4000 {
-            len |= 0x8000;
-            try!(self.write_str(&[((len >> 8) & 0xFF) as u8]));
-            try!(self.write_str(&[(len & 0xFF) as u8]));
-        } else if len < 0x200000 {
-            len |= 0xC00000;
-            try!(self.write_str(&[((len >> 16) & 0xFF) as u8]));
-            try!(self.write_str(&[((len >> 8) & 0xFF) as u8]));
-            try!(self.write_str(&[(len & 0xFF) as u8]));
-        } else if len < 0x10000000 {
-            len |= 0xE0000000;
-            try!(self.write_str(&[((len >> 24) & 0xFF) as u8]));
-            try!(self.write_str(&[((len >> 16) & 0xFF) as u8]));
-            try!(self.write_str(&[((len >> 8) & 0xFF) as u8]));
-            try!(self.write_str(&[(len & 0xFF) as u8]));
-        } else {
-            try!(self.write_str(&[0xF0 as u8]));
-            try!(self.write_str(&[((len >> 24) & 0xFF) as u8]));
-            try!(self.write_str(&[((len >> 16) & 0xFF) as u8]));
-            try!(self.write_str(&[((len >> 8) & 0xFF) as u8]));
-            try!(self.write_str(&[(len & 0xFF) as u8]));
+        match trap {
+            Some(e) => Err(e),
+            None => Ok(sentences),
         }
+    }
 
-        Ok(())
+    // Sends a command and returns all its replies, ending with the '!done' one.
+    pub fn execute(&mut self, sentence: &[String]) -> Result<Vec<Sentence>, errors::MikrotikError> {
+        if sentence.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.write_sentence(sentence)?;
+
+        self.read_all_sentences()
+    }
+
+    // Returns a tag that has not been used yet on this connection.
+    pub(crate) fn next_tag(&mut self) -> String {
+        self.next_tag += 1;
+        self.next_tag.to_string()
     }
 
-    fn read_word(&mut self) -> Result<String, io::Error> {
-        let str_len = try!(self.read_len());
-        let bytes = try!(self.read_str(str_len));
-        let result: String = (&bytes).into_iter().map(|x| *x as char).collect();
+    // Sends a command that keeps replying until it is cancelled, such as
+    // '/interface/listen' or '/log/print follow=yes', and returns an iterator over
+    // each of its '!re' replies.
+    //
+    // Dropping the listener cancels the command so the connection can be reused.
+    pub fn listen(&mut self, sentence: &[String]) -> Result<Listener<'_>, errors::MikrotikError> {
+        let tag = self.next_tag();
+        self.write_sentence(&tag_sentence(sentence, &tag))?;
 
-        Ok(result)
+        Ok(Listener::new(self, tag))
     }
 
-    fn write_word(&mut self, word: &String) -> Result<(), io::Error> {
-        try!(self.write_len(word.len()));
-        try!(self.write_str(word.as_bytes()));
+    // Streams the traffic of 'interface' every second, or of each interface in a
+    // comma separated list such as "ether1,ether2", until the monitor is dropped.
+    pub fn monitor_traffic(&mut self,
+                           interface: &str)
+                           -> Result<TrafficMonitor<'_>, errors::MikrotikError> {
+        let listener = self.listen(&monitor_traffic_sentence(interface))?;
+
+        Ok(TrafficMonitor::new(listener))
+    }
+
+    // Logs in using the method introduced in RouterOS 6.43, which sends the password
+    // in the '/login' command itself.
+    //
+    // Older routers ignore the credentials and answer with the challenge of the legacy
+    // method instead, in which case the login is completed with it.
+    pub fn login(&mut self, username: &str, pwd: &str) -> Result<(), errors::MikrotikError> {
+        let response = self.execute(&login_request(username, pwd))?;
+
+        if let Some(challenge) = login_challenge(&response)? {
+            self.execute(&legacy_login_request(username, pwd, &challenge))?;
+        }
 
         Ok(())
     }
 
-    pub fn write_sentence(&mut self, words: &Vec<String>) -> Result<(), io::Error> {
-        for w in words.iter() {
-            try!(self.write_word(&w));
+    // Logs in using the MD5 challenge-response method of RouterOS versions prior to
+    // 6.43.
+    pub fn login_legacy(&mut self, username: &str, pwd: &str) -> Result<(), errors::MikrotikError> {
+        let response = self.execute(&["/login".to_string()])?;
+
+        match login_challenge(&response)? {
+            Some(challenge) => {
+                self.execute(&legacy_login_request(username, pwd, &challenge))?;
+                Ok(())
+            }
+            None => {
+                let msg = String::from("no challenge in reply to /login");
+                Err(errors::MikrotikError::UnexpectedReply(msg))
+            }
         }
+    }
 
-        try!(self.write_word(&String::from("")));
+}
 
-        Ok(())
+impl Executor for &mut Client {
+    type Output<R> = Result<R, errors::MikrotikError>;
+
+    fn run<R>(self,
+              sentence: Result<Vec<String>, errors::MikrotikError>,
+              parse: Parser<R>)
+              -> Result<R, errors::MikrotikError> {
+        parse(&self.execute(&sentence?)?)
     }
+}
 
-    pub fn read_sentence(&mut self) -> Result<Vec<String>, io::Error> {
-        let mut words: Vec<String> = Vec::new();
+// DeadlineReader reads from the router without blocking past 'deadline', shortening
+// the read timeout of the socket as it approaches.
+struct DeadlineReader<'a> {
+    stream: &'a mut Transport,
+    read_timeout: Option<Duration>,
+    deadline: Instant,
+}
 
-        loop {
-            let w = try!(self.read_word());
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "command timed out"));
+        }
 
-            if w.is_empty() {
-                return Ok(words);
-            }
+        let timeout = match self.read_timeout {
+            Some(t) => t.min(remaining),
+            None => remaining,
+        };
+        self.stream.socket().set_read_timeout(Some(timeout))?;
+
+        self.stream.read(buf)
+    }
+}
 
-            words.push(w);
+// Connects to the first address 'addr' resolves to that accepts the connection,
+// giving up on each of them after 'timeout'.
+pub(crate) fn connect_tcp<A: ToSocketAddrs>(addr: A,
+                                            timeout: Option<Duration>)
+                                            -> Result<(TcpStream, SocketAddr), io::Error> {
+    let mut last_err = None;
+
+    for sock_addr in addr.to_socket_addrs()? {
+        let result = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&sock_addr, timeout),
+            None => TcpStream::connect(sock_addr),
+        };
+
+        match result {
+            Ok(stream) => return Ok((stream, sock_addr)),
+            Err(e) => last_err = Some(e),
         }
     }
 
-    fn is_mk_error(&self, sentence: &Vec<String>) -> Option<errors::MikrotikError> {
-        if sentence.len() == 0 {
-            return None;
+    Err(last_err.unwrap_or_else(no_address_error))
+}
+
+pub(crate) fn no_address_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput,
+                   "could not resolve to any addresses")
+}
+
+// Reads the next sentence from 'stream', feeding the decoder until one is complete.
+pub(crate) fn read_words<R: Read>(stream: &mut R,
+                                  decoder: &mut codec::Decoder)
+                                  -> Result<Vec<Vec<u8>>, io::Error> {
+    let mut buffer = [0; READ_BUFFER_SIZE];
+
+    loop {
+        if let Some(sentence) = decoder.decode_sentence()? {
+            return Ok(sentence);
         }
 
-        match sentence[0].as_str() {
-            "!trap" => {
-                let mut category: u8 = 10;
-                let mut msg = String::new();
-
-                if sentence.len() < 1 {
-                    // No category or message supplied.
-                    return Some(errors::MikrotikError::Trap {
-                        category: category,
-                        msg: msg,
-                    });
-                }
+        let x = stream.read(&mut buffer)?;
 
-                for word in &sentence[1..] {
-                    if word.starts_with("=category=") {
-                        let v: Vec<&str> = word.split("=category=").collect();
-                        category = v[1].parse::<u8>().unwrap_or(category);
-                    } else if word.starts_with("=message=") {
-                        let v: Vec<&str> = word.split("=message=").collect();
-                        msg.push_str(v[1]);
-                    }
-                }
+        if x == 0 {
+            let err = io::Error::new(io::ErrorKind::ConnectionReset,
+                                     "error reading: connection closed by remote end");
+            return Err(err);
+        }
 
-                return Some(errors::MikrotikError::Trap {
-                    category: category,
-                    msg: msg,
-                });
-            }
-            "!fatal" => {
-                if sentence.len() < 1 {
-                    let msg = String::from("No error message supplied from router!");
-                    return Some(errors::MikrotikError::Fatal(msg));
-                }
+        decoder.feed(&buffer[..x]);
+    }
+}
+
+pub(crate) fn address_query(list: &str, address: &str) -> Query {
+    Query::new().equal("list", list).equal("address", address)
+}
 
-                let msg = String::from(sentence[1].as_str());
-                return Some(errors::MikrotikError::Fatal(msg));
+pub(crate) fn monitor_traffic_sentence(interface: &str) -> Vec<String> {
+    vec![String::from("/interface/monitor-traffic"), format!("=interface={}", interface)]
+}
+
+// Merges the replies to '/system/health/print' into one sentence with an attribute per
+// sensor. RouterOS 7 sends a '!re' per sensor with its 'name' and 'value', while older
+// versions send every sensor as an attribute of a single one.
+pub(crate) fn health_sentence(response: &[Sentence]) -> Sentence {
+    let mut health = Sentence::new(Reply::Re);
+
+    for sentence in response.iter().filter(|s| s.reply == Reply::Re) {
+        match (sentence.get("name"), sentence.get("value")) {
+            (Some(name), Some(value)) => {
+                health.attributes.insert(name.to_string(), value.to_string());
             }
-            _ => return None,
+            _ => health.attributes.extend(sentence.attributes.clone()),
         }
     }
 
-    pub fn read_all_sentences(&mut self) -> Result<Vec<String>, errors::MikrotikError> {
-        let mut sentences: Vec<String> = Vec::new();
+    health
+}
 
-        loop {
-            let sentence = try!(self.read_sentence());
+pub(crate) fn identity_set_sentence(name: &str) -> Vec<String> {
+    vec![String::from("/system/identity/set"), format!("=name={}", name)]
+}
 
-            if sentence.len() == 0 {
-                continue;
-            }
+pub(crate) fn print_command(menu: &str) -> String {
+    format!("{}/print", menu.trim_end_matches('/'))
+}
 
-            if let Some(e) = self.is_mk_error(&sentence) {
-                return Err(e);
-            }
+// Returns a copy of 'sentence' with the '.tag=' attribute appended.
+pub(crate) fn tag_sentence(sentence: &[String], tag: &str) -> Vec<String> {
+    let mut words = sentence.to_vec();
+    words.push(format!(".tag={}", tag));
 
-            sentences.extend_from_slice(&sentence);
+    words
+}
 
-            if sentence[0] == "!done" {
-                break;
+// Computes the response to the challenge sent by the router during the legacy login.
+pub(crate) fn login_response(pwd: &str, challenge: &[u8]) -> String {
+    let mut md = Md5::new();
+    md.input(&[0]);
+    md.input(pwd.as_bytes());
+    md.input(challenge);
+
+    md.result_str()
+}
+
+pub(crate) fn login_request(username: &str, pwd: &str) -> Vec<String> {
+    vec!["/login".to_string(), format!("=name={}", username), format!("=password={}", pwd)]
+}
+
+pub(crate) fn legacy_login_request(username: &str, pwd: &str, challenge: &[u8]) -> Vec<String> {
+    vec!["/login".to_string(),
+         format!("=name={}", username),
+         format!("=response=00{}", login_response(pwd, challenge))]
+}
+
+// Returns the challenge found in the reply to '/login', if any.
+pub(crate) fn login_challenge(response: &[Sentence])
+                              -> Result<Option<Vec<u8>>, errors::MikrotikError> {
+    match response.iter().find_map(|s| s.get("ret")) {
+        Some(ret) => Ok(Some(utils::unhexlify(ret)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MikrotikError;
+    use crate::models;
+    use crate::rate::{Rate, RateLimit};
+    use crate::resource::Resource;
+    use crate::types::{IpPrefix, ListAddress, MacAddress, RosDuration};
+    use crate::test_utils::{mock_router, words};
+
+    use std::net::{Ipv6Addr, SocketAddrV4, TcpListener};
+    use std::thread;
+
+    #[test]
+    fn test_login() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/login", "=name=admin", "=password=secret"], &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        client.login("admin", "secret").unwrap();
+    }
+
+    #[test]
+    fn test_login_falls_back_to_legacy_method() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/login", "=name=admin", "=password=secret"],
+                        &[words(&["!done", "=ret=ebddd18303a54111e2dea05a92ab46b4"])]);
+            conn.expect(&["/login",
+                          "=name=admin",
+                          "=response=007319531c22b6b85e160d6ac355c1df2e"],
+                        &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        client.login("admin", "secret").unwrap();
+    }
+
+    #[test]
+    fn test_login_with_invalid_credentials() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/login", "=name=admin", "=password=wrong"],
+                        &[words(&["!trap", "=message=invalid user name or password (6)"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        match client.login("admin", "wrong") {
+            Err(MikrotikError::Trap { msg, .. }) => {
+                assert_eq!(msg, "invalid user name or password (6)")
             }
+            _ => panic!("expected a trap error"),
         }
-
-        Ok(sentences)
     }
 
-    fn execute(&mut self, sentence: &Vec<String>) -> Result<Vec<String>, errors::MikrotikError> {
-        if sentence.len() == 0 {
-            return Ok(vec![]);
+    #[test]
+    fn test_command_after_trap() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/address-list/add",
+                          "=address=10.0.0.1",
+                          "=list=blocked",
+                          "=disabled=false"],
+                        &[words(&["!trap", "=message=failure: already have such entry"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/firewall/address-list/print",
+                          "?list=blocked",
+                          "?address=10.0.0.1"],
+                        &[words(&["!re", "=.id=*1", "=list=blocked", "=address=10.0.0.1"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let entry = models::IPAddress {
+            address: ListAddress::parse("10.0.0.1").unwrap(),
+            list: String::from("blocked"),
+            ..models::IPAddress::new()
+        };
+
+        match client.add_address(&entry) {
+            Err(MikrotikError::Trap { msg, .. }) => {
+                assert_eq!(msg, "failure: already have such entry")
+            }
+            _ => panic!("expected a trap error"),
         }
 
-        try!(self.write_sentence(sentence));
+        let entry = client.find_address("blocked", "10.0.0.1").unwrap().unwrap();
+        assert_eq!(entry.id, "*1");
+    }
 
-        let response = try!(self.read_all_sentences());
-        if response.len() == 0 {
-            return Ok(vec![]);
+    #[test]
+    fn test_legacy_login_without_challenge() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/login"], &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        match client.login_legacy("admin", "secret") {
+            Err(MikrotikError::UnexpectedReply(_)) => {}
+            _ => panic!("expected an unexpected reply error"),
         }
+    }
 
-        Ok(response)
+    #[test]
+    fn test_address_list_values_containing_keys() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/address-list/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=list=list=blocked",
+                                  "=address=10.0.0.1",
+                                  "=comment=address=10.0.0.2"]),
+                          words(&["!re", "=.id=*2", "=list=", "=address=10.0.0.3"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let addresses = client.get_address_list().unwrap();
+
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[0].list, "list=blocked");
+        assert_eq!(addresses[0].address.to_string(), "10.0.0.1");
+        assert_eq!(addresses[1].list, "");
+        assert_eq!(addresses[1].address.to_string(), "10.0.0.3");
     }
 
-    pub fn login(&mut self, username: &str, pwd: &str) -> Result<(), errors::MikrotikError> {
-        let mut login_request = vec!["/login".to_string()];
-        let response = try!(self.execute(&login_request));
+    #[test]
+    fn test_address_list_where() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/address-list/print",
+                          "=.proplist=.id,address",
+                          "?list=blocked"],
+                        &[words(&["!re", "=.id=*1", "=address=10.0.0.1"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let query = Query::new().equal("list", "blocked").proplist(&[".id", "address"]);
+        let addresses = client.get_address_list_where(&query).unwrap();
+
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses[0].address.to_string(), "10.0.0.1");
+        assert_eq!(addresses[0].list, "");
+    }
 
-        let hexstr: Vec<&str> = response[1].split("=ret=").collect();
-        let challenge = try!(utils::unhexlify(hexstr[1]));
+    #[test]
+    fn test_add_set_and_remove() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/address-list/add",
+                          "=address=10.0.0.1",
+                          "=list=suspended",
+                          "=timeout=1d",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*5"])]);
+            conn.expect(&["/ip/firewall/address-list/print",
+                          "?list=suspended",
+                          "?address=10.0.0.1"],
+                        &[words(&["!re",
+                                  "=.id=*5",
+                                  "=list=suspended",
+                                  "=address=10.0.0.1",
+                                  "=timeout=23h59m58s",
+                                  "=creation-time=jan/02/2024 10:00:00",
+                                  "=dynamic=true",
+                                  "=disabled=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/firewall/address-list/set",
+                          "=.id=*5",
+                          "=address=10.0.0.1",
+                          "=list=suspended",
+                          "=comment=unpaid",
+                          "=disabled=false"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/ip/firewall/address-list/set",
+                          "=.id=*5",
+                          "=address=10.0.0.1",
+                          "=list=suspended",
+                          "=comment=",
+                          "=disabled=false"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/ip/firewall/address-list/remove", "=.id=*5"], &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let entry = models::IPAddress {
+            address: ListAddress::parse("10.0.0.1").unwrap(),
+            list: String::from("suspended"),
+            timeout: Some(RosDuration::from_secs(24 * 3600)),
+            ..models::IPAddress::new()
+        };
+
+        assert_eq!(client.add_address(&entry).unwrap(), "*5");
+
+        let mut entry = client.find_address("suspended", "10.0.0.1").unwrap().unwrap();
+        assert_eq!(entry.id, "*5");
+        assert!(entry.dynamic);
+        assert_eq!(entry.timeout, RosDuration::parse("23:59:58"));
+        assert_eq!(entry.creation_time.unwrap().to_string(), "jan/02/2024 10:00:00");
+
+        entry.comment = Some(String::from("unpaid"));
+        client.set_address(&entry).unwrap();
+        entry.comment = Some(String::new());
+        client.set_address(&entry).unwrap();
+        client.remove_address(&entry.id).unwrap();
+    }
 
-        let mut md = Md5::new();
-        md.input(&[0]);
-        md.input(pwd.as_bytes());
-        md.input(challenge.as_slice());
+    #[test]
+    fn test_queue_plan_change() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/queue/simple/add",
+                          "=name=customer-1",
+                          "=target=10.0.0.1/32",
+                          "=limit-at=5M/10M",
+                          "=max-limit=10M/20M",
+                          "=burst-limit=0/0",
+                          "=burst-threshold=0/0",
+                          "=burst-time=0s/0s",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*A"])]);
+            conn.expect(&["/queue/simple/print", "?name=customer-1"],
+                        &[words(&["!re",
+                                  "=.id=*A",
+                                  "=name=customer-1",
+                                  "=target=10.0.0.1/32",
+                                  "=parent=none",
+                                  "=queue=default-small/default-small",
+                                  "=priority=8/8",
+                                  "=limit-at=5M/10M",
+                                  "=max-limit=10M/20M",
+                                  "=burst-limit=0/0",
+                                  "=burst-threshold=0/0",
+                                  "=burst-time=0s/0s",
+                                  "=disabled=false",
+                                  "=dynamic=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/queue/simple/set",
+                          "=.id=*A",
+                          "=name=customer-1",
+                          "=target=10.0.0.1/32",
+                          "=parent=none",
+                          "=queue=default-small/default-small",
+                          "=priority=8/8",
+                          "=limit-at=5M/10M",
+                          "=max-limit=20M/40M",
+                          "=burst-limit=0/0",
+                          "=burst-threshold=0/0",
+                          "=burst-time=0s/0s",
+                          "=comment=plan upgrade",
+                          "=disabled=false"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/queue/simple/disable", "=.id=*A"], &[words(&["!done"])]);
+            conn.expect(&["/queue/simple/enable", "=.id=*A"], &[words(&["!done"])]);
+            conn.expect(&["/queue/simple/remove", "=.id=*A"], &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let queue = models::Client {
+            name: String::from("customer-1"),
+            target: String::from("10.0.0.1/32"),
+            limit_at: Some(RateLimit::new(5_000_000, 10_000_000)),
+            max_limit: RateLimit::parse("10M/20M").unwrap(),
+            ..models::Client::new()
+        };
+
+        assert_eq!(client.add_queue(&queue).unwrap(), "*A");
+
+        let mut queue = client.find_queue("customer-1").unwrap().unwrap();
+        assert_eq!(queue.priority.as_deref(), Some("8/8"));
+
+        assert!(queue.burst_limit.is_unlimited());
+
+        queue.max_limit.upload *= 2;
+        queue.max_limit.download *= 2;
+        queue.comment = Some(String::from("plan upgrade"));
+        client.set_queue(&queue).unwrap();
+
+        client.disable_queue(&queue.id).unwrap();
+        client.enable_queue(&queue.id).unwrap();
+        client.remove_queue(&queue.id).unwrap();
+    }
 
-        login_request.push(format!("=name={}", username));
-        login_request.push(format!("=response=00{}", md.result_str()));
+    #[test]
+    fn test_queue_tree_with_pcq() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/queue/type/add",
+                          "=name=pcq-download",
+                          "=kind=pcq",
+                          "=pcq-rate=2M",
+                          "=pcq-classifier=dst-address"],
+                        &[words(&["!done", "=ret=*10"])]);
+            conn.expect(&["/queue/tree/add",
+                          "=name=download",
+                          "=parent=global",
+                          "=packet-mark=customers",
+                          "=queue=pcq-download",
+                          "=max-limit=100M",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*1"])]);
+            conn.expect(&["/queue/tree/print", "?name=download"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=name=download",
+                                  "=parent=global",
+                                  "=packet-mark=customers",
+                                  "=queue=pcq-download",
+                                  "=priority=8",
+                                  "=limit-at=0",
+                                  "=max-limit=100000000",
+                                  "=burst-time=0s",
+                                  "=invalid=false",
+                                  "=disabled=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/queue/type/print"],
+                        &[words(&["!re",
+                                  "=.id=*10",
+                                  "=name=pcq-download",
+                                  "=kind=pcq",
+                                  "=pcq-rate=2000000",
+                                  "=pcq-limit=50",
+                                  "=pcq-classifier=dst-address",
+                                  "=pcq-total-limit=2000"]),
+                          words(&["!re", "=.id=*1", "=name=default", "=kind=pfifo",
+                                  "=default=true"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let pcq = models::QueueType::pcq("pcq-download", "dst-address", Rate(2_000_000));
+        assert_eq!(client.add_queue_type(&pcq).unwrap(), "*10");
+
+        let entry = models::QueueTree {
+            name: String::from("download"),
+            parent: String::from("global"),
+            packet_mark: Some(String::from("customers")),
+            queue: Some(pcq.name.clone()),
+            max_limit: Rate::parse("100M"),
+            ..models::QueueTree::new()
+        };
+        assert_eq!(client.add_queue_tree(&entry).unwrap(), "*1");
+
+        let entry = client.find_queue_tree("download").unwrap().unwrap();
+        assert_eq!(entry.max_limit, Some(Rate(100_000_000)));
+        assert_eq!(entry.priority, Some(8));
+        assert_eq!(entry.burst_time, Some(RosDuration::from_secs(0)));
+
+        let types = client.get_queue_types().unwrap();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0].pcq_rate, Some(Rate::from(2_000_000)));
+        assert_eq!(types[0].pcq_total_limit, Some(2000));
+        assert_eq!(types[1].pcq_rate, None);
+        assert!(types[1].default);
+    }
 
-        try!(self.execute(&login_request));
+    #[test]
+    fn test_interfaces() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/interface/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=name=ether1",
+                                  "=type=ether",
+                                  "=mtu=1500",
+                                  "=actual-mtu=1500",
+                                  "=mac-address=4C:5E:0C:11:22:33",
+                                  "=rx-byte=123456789012",
+                                  "=tx-byte=1024",
+                                  "=rx-packet=100",
+                                  "=tx-packet=10",
+                                  "=running=true",
+                                  "=disabled=false"]),
+                          words(&["!re",
+                                  "=.id=*2",
+                                  "=name=bridge1",
+                                  "=type=bridge",
+                                  "=mtu=auto",
+                                  "=actual-mtu=1500",
+                                  "=running=false",
+                                  "=disabled=true"]),
+                          words(&["!done"])]);
+            conn.expect(&["/interface/set", "=.id=*1", "=name=ether1", "=mtu=1500",
+                          "=comment=uplink", "=disabled=false"],
+                        &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let interfaces = client.get_interfaces().unwrap();
+
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].r#type, "ether");
+        assert_eq!(interfaces[0].actual_mtu, Some(1500));
+        assert_eq!(interfaces[0].rx_byte, 123_456_789_012);
+        assert!(interfaces[0].running);
+        assert_eq!(interfaces[1].mtu.as_deref(), Some("auto"));
+        assert_eq!(interfaces[1].rx_byte, 0);
+        assert!(interfaces[1].disabled);
+
+        let mut uplink = interfaces[0].clone();
+        uplink.comment = Some(String::from("uplink"));
+        client.set_interface(&uplink).unwrap();
+    }
 
-        Ok(())
+    #[test]
+    fn test_pin_dhcp_lease() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/dhcp-server/lease/print", "?mac-address=4C:5E:0C:11:22:33"],
+                        &[words(&["!re",
+                                  "=.id=*7",
+                                  "=address=192.168.88.254",
+                                  "=mac-address=4C:5E:0C:11:22:33",
+                                  "=server=lan",
+                                  "=status=bound",
+                                  "=expires-after=9m51s",
+                                  "=last-seen=9s",
+                                  "=host-name=customer-router",
+                                  "=dynamic=true",
+                                  "=blocked=false",
+                                  "=disabled=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/dhcp-server/lease/make-static", "=.id=*7"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/ip/dhcp-server/lease/add",
+                          "=address=192.168.88.10",
+                          "=mac-address=4C:5E:0C:44:55:66",
+                          "=server=lan",
+                          "=comment=customer 42",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*8"])]);
+            conn.expect(&["/ip/dhcp-server/lease/disable", "=.id=*8"], &[words(&["!done"])]);
+            conn.expect(&["/ip/dhcp-server/lease/remove", "=.id=*8"], &[words(&["!done"])]);
+            conn.expect(&["/ip/dhcp-server/network/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=address=192.168.88.0/24",
+                                  "=gateway=192.168.88.1",
+                                  "=dns-server=192.168.88.1,1.1.1.1"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        let mac = MacAddress::parse("4c:5e:0c:11:22:33").unwrap();
+        let lease = client.find_dhcp_lease(&mac).unwrap().unwrap();
+        assert!(lease.dynamic);
+        assert_eq!(lease.expires_after, RosDuration::parse("9m51s"));
+        assert_eq!(lease.host_name.as_deref(), Some("customer-router"));
+        client.make_dhcp_lease_static(&lease.id).unwrap();
+
+        let lease = models::DhcpLease {
+            address: String::from("192.168.88.10"),
+            mac_address: MacAddress::parse("4C:5E:0C:44:55:66").unwrap(),
+            server: Some(String::from("lan")),
+            comment: Some(String::from("customer 42")),
+            ..models::DhcpLease::new()
+        };
+        let id = client.add_dhcp_lease(&lease).unwrap();
+        client.disable_dhcp_lease(&id).unwrap();
+        client.remove_dhcp_lease(&id).unwrap();
+
+        let networks = client.get_dhcp_networks().unwrap();
+        assert_eq!(networks[0].address, IpPrefix::parse("192.168.88.0/24").unwrap());
+        assert_eq!(networks[0].gateway.as_deref(), Some("192.168.88.1"));
+        assert_eq!(networks[0].netmask, None);
     }
 
-    pub fn get_address_list(&mut self) -> Result<Vec<models::IPAddress>, errors::MikrotikError> {
-        let request = vec!["/ip/firewall/address-list/print".to_string()];
-        let response = try!(self.execute(&request));
+    #[test]
+    fn test_suspend_ppp_customer() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ppp/secret/add",
+                          "=name=customer-1",
+                          "=password=secret",
+                          "=service=pppoe",
+                          "=profile=10M",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*3"])]);
+            conn.expect(&["/ppp/secret/disable", "=.id=*3"], &[words(&["!done"])]);
+            conn.expect(&["/ppp/active/print", "?name=customer-1"],
+                        &[words(&["!re",
+                                  "=.id=*80000001",
+                                  "=name=customer-1",
+                                  "=service=pppoe",
+                                  "=caller-id=4C:5E:0C:11:22:33",
+                                  "=address=10.10.0.2",
+                                  "=uptime=1d2h3m4s",
+                                  "=radius=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ppp/active/remove", "=.id=*80000001"], &[words(&["!done"])]);
+            conn.expect(&["/ppp/profile/print"],
+                        &[words(&["!re", "=.id=*0", "=name=default", "=default=true"]),
+                          words(&["!re",
+                                  "=.id=*1",
+                                  "=name=10M",
+                                  "=rate-limit=5M/10M 10M/20M 4M/8M 16/16",
+                                  "=only-one=yes"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let secret = models::PppSecret {
+            name: String::from("customer-1"),
+            password: String::from("secret"),
+            service: Some(String::from("pppoe")),
+            profile: Some(String::from("10M")),
+            ..models::PppSecret::new()
+        };
+
+        let id = client.add_ppp_secret(&secret).unwrap();
+        client.disable_ppp_secret(&id).unwrap();
+
+        let session = client.find_ppp_active("customer-1").unwrap().unwrap();
+        assert_eq!(session.uptime, Some(RosDuration::from_secs(93_784)));
+        client.disconnect_ppp(&session.id).unwrap();
+
+        let profiles = client.get_ppp_profiles().unwrap();
+        assert!(profiles[0].default);
+        assert_eq!(profiles[0].rate_limit, None);
+
+        let limit = profiles[1].rate_limit.unwrap();
+        assert_eq!(limit.rate.download, 10_000_000);
+        assert_eq!(limit.burst_limit.unwrap().download, 20_000_000);
+        assert_eq!(limit.burst_time.unwrap().download.as_secs(), 16);
+        assert_eq!(limit.priority, None);
+    }
 
-        let mut addresses: Vec<models::IPAddress> = Vec::new();
-        let mut address = models::IPAddress::new();
-        let mut is_first = true;
+    #[test]
+    fn test_hotspot_voucher() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/hotspot/user/add",
+                          "=name=voucher-1",
+                          "=password=1234",
+                          "=profile=1h",
+                          "=limit-uptime=1h",
+                          "=limit-bytes-total=1073741824",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*4"])]);
+            conn.expect(&["/ip/hotspot/user/print", "?name=voucher-1"],
+                        &[words(&["!re",
+                                  "=.id=*4",
+                                  "=name=voucher-1",
+                                  "=password=1234",
+                                  "=profile=1h",
+                                  "=limit-uptime=1h",
+                                  "=limit-bytes-total=1073741824",
+                                  "=uptime=59m",
+                                  "=bytes-in=1000",
+                                  "=bytes-out=200000",
+                                  "=dynamic=false",
+                                  "=disabled=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/hotspot/active/print", "?user=voucher-1"],
+                        &[words(&["!re",
+                                  "=.id=*B",
+                                  "=server=hotspot1",
+                                  "=user=voucher-1",
+                                  "=address=10.5.50.10",
+                                  "=mac-address=4C:5E:0C:11:22:33",
+                                  "=login-by=http-chap",
+                                  "=uptime=59m",
+                                  "=session-time-left=1m",
+                                  "=idle-time=0s",
+                                  "=bytes-in=1000",
+                                  "=bytes-out=200000"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/hotspot/active/remove", "=.id=*B"], &[words(&["!done"])]);
+            conn.expect(&["/ip/hotspot/user/profile/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=name=1h",
+                                  "=shared-users=1",
+                                  "=rate-limit=2M/4M",
+                                  "=session-timeout=0s",
+                                  "=idle-timeout=none",
+                                  "=keepalive-timeout=2m"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let user = models::HotspotUser {
+            name: String::from("voucher-1"),
+            password: String::from("1234"),
+            profile: Some(String::from("1h")),
+            limit_uptime: Some(RosDuration::from_secs(3600)),
+            limit_bytes_total: Some(1 << 30),
+            ..models::HotspotUser::new()
+        };
+        assert_eq!(client.add_hotspot_user(&user).unwrap(), "*4");
+
+        let user = client.find_hotspot_user("voucher-1").unwrap().unwrap();
+        assert_eq!(user.uptime, Some(RosDuration::from_secs(59 * 60)));
+        assert_eq!(user.bytes_in + user.bytes_out, 201_000);
+
+        let session = client.find_hotspot_active(&user.name).unwrap().unwrap();
+        assert_eq!(session.session_time_left, Some(RosDuration::from_secs(60)));
+        client.kick_hotspot_user(&session.id).unwrap();
+
+        let profiles = client.get_hotspot_user_profiles().unwrap();
+        assert_eq!(profiles[0].rate_limit.unwrap().rate, RateLimit::new(2_000_000, 4_000_000));
+        assert_eq!(profiles[0].idle_timeout.as_deref(), Some("none"));
+        assert_eq!(profiles[0].keepalive_timeout, Some(RosDuration::from_secs(120)));
+    }
 
-        for word in response {
-            if word.starts_with("!re") {
-                if is_first {
-                    is_first = false;
-                    continue;
-                }
+    #[test]
+    fn test_firewall_rules() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/firewall/nat/add",
+                          "=chain=dstnat",
+                          "=action=dst-nat",
+                          "=protocol=tcp",
+                          "=dst-port=8080",
+                          "=in-interface=ether1",
+                          "=to-addresses=192.168.88.10",
+                          "=to-ports=80",
+                          "=comment=customer 42 web",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*9"])]);
+            conn.expect(&["/ip/firewall/nat/move", "=numbers=*9", "=destination=*2"],
+                        &[words(&["!done"])]);
+            conn.expect(&["/ip/firewall/filter/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=chain=forward",
+                                  "=action=drop",
+                                  "=src-address-list=suspended",
+                                  "=log=false",
+                                  "=bytes=52000",
+                                  "=packets=40",
+                                  "=invalid=false",
+                                  "=dynamic=false",
+                                  "=disabled=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/firewall/filter/disable", "=.id=*1"], &[words(&["!done"])]);
+            conn.expect(&["/ip/firewall/mangle/move", "=numbers=*4"], &[words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let forward = models::NatRule {
+            chain: String::from("dstnat"),
+            action: Some(String::from("dst-nat")),
+            protocol: Some(String::from("tcp")),
+            dst_port: Some(String::from("8080")),
+            in_interface: Some(String::from("ether1")),
+            to_addresses: Some(String::from("192.168.88.10")),
+            to_ports: Some(String::from("80")),
+            comment: Some(String::from("customer 42 web")),
+            ..models::NatRule::new()
+        };
+
+        let id = client.add_nat_rule(&forward).unwrap();
+        client.move_nat_rule(&id, Some("*2")).unwrap();
+
+        let rules = client.get_filter_rules().unwrap();
+        assert_eq!(rules[0].src_address_list.as_deref(), Some("suspended"));
+        assert_eq!(rules[0].log, Some(false));
+        assert_eq!(rules[0].packets, 40);
+        client.disable_filter_rule(&rules[0].id).unwrap();
+
+        client.move_mangle_rule("*4", None).unwrap();
+    }
 
-                addresses.push(address.clone());
-                address = models::IPAddress::new();
-            } else if word.starts_with("=.id=") {
-                let v: Vec<&str> = word.split("=.id=").collect();
-                address.id.push_str(v[1]);
-            } else if word.starts_with("=list=") {
-                let v: Vec<&str> = word.split("=list=").collect();
-                address.list.push_str(v[1]);
-            } else if word.starts_with("=address=") {
-                let v: Vec<&str> = word.split("=address=").collect();
-                address.address.push_str(v[1]);
-            } else if word.starts_with("!done") {
-                addresses.push(address.clone());
-                break;
-            }
-        }
+    #[test]
+    fn test_addresses_routes_and_arp() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/ip/address/add",
+                          "=address=10.20.0.1/30",
+                          "=interface=ether2",
+                          "=disabled=false"],
+                        &[words(&["!done", "=ret=*6"])]);
+            conn.expect(&["/ip/route/print"],
+                        &[words(&["!re",
+                                  "=.id=*1",
+                                  "=dst-address=0.0.0.0/0",
+                                  "=gateway=10.20.0.2",
+                                  "=immediate-gw=10.20.0.2%ether2",
+                                  "=distance=1",
+                                  "=routing-table=main",
+                                  "=active=true",
+                                  "=static=true",
+                                  "=disabled=false"]),
+                          words(&["!re",
+                                  "=.id=*2",
+                                  "=dst-address=10.20.0.0/30",
+                                  "=gateway=ether2",
+                                  "=pref-src=",
+                                  "=distance=0",
+                                  "=active=true",
+                                  "=dynamic=true",
+                                  "=connect=true"]),
+                          words(&["!done"])]);
+            conn.expect(&["/ip/arp/print"],
+                        &[words(&["!re",
+                                  "=.id=*3",
+                                  "=address=10.20.0.2",
+                                  "=mac-address=4C:5E:0C:11:22:33",
+                                  "=interface=ether2",
+                                  "=dynamic=true",
+                                  "=complete=true"]),
+                          words(&["!re",
+                                  "=.id=*4",
+                                  "=address=10.20.0.3",
+                                  "=interface=ether2",
+                                  "=complete=false"]),
+                          words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        let address = models::InterfaceAddress {
+            address: IpPrefix::parse("10.20.0.1/30").unwrap(),
+            interface: String::from("ether2"),
+            ..models::InterfaceAddress::new()
+        };
+        assert_eq!(client.add_ip_address(&address).unwrap(), "*6");
+
+        let routes = client.get_routes().unwrap();
+        let default_route = routes.iter().find(|r| r.dst_address.len == 0).unwrap();
+        assert!(default_route.active && default_route.r#static);
+        assert_eq!(default_route.gateway.as_deref(), Some("10.20.0.2"));
+        assert!(routes[1].connect);
+        assert_eq!(routes[1].pref_src, None);
+        assert!(routes[1].dst_address.contains(address.address.address));
+
+        let arp = client.get_arp_entries().unwrap();
+        assert_eq!(arp[0].mac_address, MacAddress::parse("4C:5E:0C:11:22:33"));
+        assert_eq!(arp[0].address, "10.20.0.2".parse().ok());
+        assert_eq!(arp[1].mac_address, None);
+        assert!(!arp[1].complete);
+    }
 
-        Ok(addresses)
+    #[test]
+    fn test_system_monitoring() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/system/resource/print"],
+                        &[words(&["!re",
+                                  "=uptime=2w3d04:05:06",
+                                  "=version=7.12.1 (stable)",
+                                  "=build-time=Nov/17/2023 11:38:45",
+                                  "=free-memory=104857600",
+                                  "=total-memory=268435456",
+                                  "=cpu=ARMv7",
+                                  "=cpu-count=4",
+                                  "=cpu-frequency=716",
+                                  "=cpu-load=12",
+                                  "=free-hdd-space=8388608",
+                                  "=total-hdd-space=16777216",
+                                  "=architecture-name=arm",
+                                  "=board-name=hAP ac^2",
+                                  "=platform=MikroTik"]),
+                          words(&["!done"])]);
+            conn.expect(&["/system/identity/set", "=name=site-7"], &[words(&["!done"])]);
+            conn.expect(&["/system/identity/print"],
+                        &[words(&["!re", "=name=site-7"]), words(&["!done"])]);
+            conn.expect(&["/system/health/print"],
+                        &[words(&["!re", "=.id=*D", "=name=voltage", "=value=24.1", "=type=V"]),
+                          words(&["!re", "=.id=*E", "=name=temperature", "=value=41",
+                                  "=type=C"]),
+                          words(&["!done"])]);
+            conn.expect(&["/system/clock/print"],
+                        &[words(&["!re",
+                                  "=time=10:00:00",
+                                  "=date=jan/02/2024",
+                                  "=time-zone-name=Europe/Riga",
+                                  "=gmt-offset=+02:00",
+                                  "=dst-active=false"]),
+                          words(&["!done"])]);
+            conn.expect(&["/system/routerboard/print"],
+                        &[words(&["!re", "=routerboard=false"]), words(&["!done"])]);
+        });
+
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        let resource = client.system_resource().unwrap();
+        assert_eq!(resource.uptime, RosDuration::parse("2w3d4h5m6s").unwrap());
+        assert_eq!(resource.version, "7.12.1 (stable)");
+        assert_eq!(resource.build_time.unwrap().to_string(), "nov/17/2023 11:38:45");
+        assert_eq!(resource.total_memory - resource.free_memory, 163_577_856);
+        assert_eq!(resource.cpu_load, 12);
+        assert_eq!(resource.board_name, "hAP ac^2");
+
+        client.set_identity("site-7").unwrap();
+        assert_eq!(client.identity().unwrap(), "site-7");
+
+        let health = client.health().unwrap();
+        assert_eq!(health.voltage, Some(24.1));
+        assert_eq!(health.temperature, Some(41.0));
+        assert_eq!(health.fan1_speed, None);
+
+        let clock = client.clock().unwrap();
+        assert_eq!(clock.date_time().to_string(), "jan/02/2024 10:00:00");
+        assert_eq!(clock.gmt_offset.as_deref(), Some("+02:00"));
+
+        let routerboard = client.routerboard().unwrap();
+        assert!(!routerboard.routerboard);
+        assert_eq!(routerboard.model, None);
     }
 
-    pub fn get_queue_list(&mut self) -> Result<Vec<models::Client>, errors::MikrotikError> {
-        let request = vec!["/queue/simple/print".to_string()];
-        let response = try!(self.execute(&request));
+    #[test]
+    fn test_health_sentence() {
+        let response = [Sentence::parse(&words(&["!re", "=voltage=12.2", "=temperature=38"]))
+                            .unwrap(),
+                        Sentence::new(Reply::Done)];
+        let health = models::Health::from_sentence(&health_sentence(&response)).unwrap();
 
-        let mut clients: Vec<models::Client> = Vec::new();
-        let mut client = models::Client::new();
-        let mut is_first = true;
+        assert_eq!(health.voltage, Some(12.2));
+        assert_eq!(health.temperature, Some(38.0));
+        assert_eq!(health.cpu_temperature, None);
+    }
 
-        for word in response {
-            if word.starts_with("!re") {
-                if is_first {
-                    is_first = false;
-                    continue;
-                }
+    #[test]
+    fn test_single_item_missing() {
+        let port = mock_router(|conn| {
+            conn.expect(&["/system/clock/print"], &[words(&["!done"])]);
+        });
 
-                clients.push(client.clone());
-                client = models::Client::new();
-            } else if word.starts_with("=.id=") {
-                let v: Vec<&str> = word.split("=.id=").collect();
-                client.id.push_str(v[1]);
-            } else if word.starts_with("=name=") {
-                let v: Vec<&str> = word.split("=name=").collect();
-                client.name.push_str(v[1]);
-            } else if word.starts_with("=target=") {
-                let v: Vec<&str> = word.split("=target=").collect();
-                client.target.push_str(v[1]);
-            } else if word.starts_with("=max-limit=") {
-                let v: Vec<&str> = word.split("=max-limit=").collect();
-                client.max_limit.push_str(v[1]);
-            } else if word.starts_with("=burst-limit=") {
-                let v: Vec<&str> = word.split("=burst-limit=").collect();
-                client.burst_limit.push_str(v[1]);
-            } else if word.starts_with("=burst-threshold=") {
-                let v: Vec<&str> = word.split("=burst-threshold=").collect();
-                client.burst_threshold.push_str(v[1]);
-            } else if word.starts_with("=burst-time=") {
-                let v: Vec<&str> = word.split("=burst-time=").collect();
-                client.burst_time.push_str(v[1]);
-            } else if word.starts_with("!done") {
-                clients.push(client.clone());
-                break;
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+        match client.clock() {
+            Err(MikrotikError::UnexpectedReply(msg)) => {
+                assert_eq!(msg, "no reply to /system/clock/print")
             }
+            _ => panic!("expected an unexpected reply error"),
         }
-
-        Ok(clients)
     }
 
-    fn talk(&mut self,
-            words: &Vec<String>)
-            -> Result<Vec<(String, BTreeMap<String, String>)>, io::Error> {
-        if words.len() == 0 {
-            return Ok(vec![]);
-        }
+    #[test]
+    fn test_encoding_round_trip() {
+        let port = mock_router(|conn| {
+            let request = conn.read_words();
+            assert_eq!(request[1], b"=comment=\xC8\xE2\xE0\xED");
 
-        try!(self.write_sentence(words));
-        let mut r: Vec<(String, BTreeMap<String, String>)> = Vec::new();
+            conn.reply(&[vec![b"!re".to_vec(), b"=name=\xCF\xE5\xF2\xF0".to_vec()],
+                         vec![b"!done".to_vec()]]);
+        });
 
-        loop {
-            let sentence = try!(self.read_sentence());
-            if sentence.len() == 0 {
-                continue;
-            }
+        let mut client = ClientBuilder::new()
+            .encoding(Encoding::Windows1251)
+            .connect(("127.0.0.1", port))
+            .unwrap();
+        let response = client.execute(&words(&["/queue/simple/set", "=comment=Иван"])).unwrap();
 
-            let reply = &sentence[0];
-            let mut attrs: BTreeMap<String, String> = BTreeMap::new();
-
-            for word in &sentence[1..] {
-                match word[1..].find("=") {
-                    Some(n) => {
-                        attrs.insert(word[..n + 1].to_string(), word[(n + 2)..].to_string());
-                    }
-                    None => {
-                        attrs.insert(word.clone(), String::from(""));
-                    }
-                }
-            }
+        assert_eq!(response[0].get("name"), Some("Петр"));
+    }
 
-            r.push((reply.clone(), attrs));
-            if reply == "!done" {
-                return Ok(r);
-            }
-        }
+    #[test]
+    fn test_login_response() {
+        let challenge = utils::unhexlify("ebddd18303a54111e2dea05a92ab46b4").unwrap();
+
+        assert_eq!(login_response("secret", &challenge),
+                   "7319531c22b6b85e160d6ac355c1df2e");
     }
 
-    pub fn old_login(&mut self, username: &str, pwd: &str) -> Result<(), io::Error> {
-        let mut challenge: Vec<u8> = Vec::new();
-        let mut login_request = vec![r"/login".to_string()];
+    #[test]
+    fn test_connect_tries_every_resolved_address() {
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let port = mock_router(|_| {});
+        let open = SocketAddr::V4(SocketAddrV4::new([127, 0, 0, 1].into(), port));
 
-        for (_, attrs) in try!(self.talk(&login_request)) {
-            challenge = utils::unhexlify(&attrs["=ret"]).unwrap();
-        }
+        let client = Client::connect(&[closed, open][..]).unwrap();
+        assert_eq!(client.peer_addr(), open);
+    }
+
+    #[test]
+    fn test_connect_by_hostname() {
+        let port = mock_router(|_| {});
 
-        let mut md = Md5::new();
-        md.input(&[0]);
-        md.input(pwd.as_bytes());
-        md.input(&challenge[..]);
+        let client = Client::connect(("localhost", port)).unwrap();
+        assert_eq!(client.peer_addr().port(), port);
+    }
 
-        login_request.push(format!("=name={}", username));
-        login_request.push(format!("=response=00{}", md.result_str()));
+    #[test]
+    fn test_connect_ipv6() {
+        // Skip when the host has no IPv6 loopback.
+        let listener = match TcpListener::bind("[::1]:0") {
+            Ok(l) => l,
+            Err(_) => return,
+        };
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || listener.accept());
+
+        let client = Client::connect(format!("[::1]:{}", port)).unwrap();
+        assert_eq!(client.peer_addr().ip(), Ipv6Addr::LOCALHOST);
+    }
 
-        try!(self.talk(&login_request));
+    #[test]
+    fn test_connect_without_addresses() {
+        let addrs: &[SocketAddr] = &[];
 
-        Ok(())
+        match Client::connect(addrs) {
+            Err(errors::CreateClientError::IoError(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::InvalidInput)
+            }
+            _ => panic!("expected an io error"),
+        }
     }
 }
@@ -0,0 +1,329 @@
+use crate::errors::CodecError;
+
+// Largest word length that can be described by a length prefix.
+pub const MAX_WORD_LEN: usize = 0xFFFFFFFF;
+
+// Appends the RouterOS API length prefix for a word of 'len' bytes to 'buf'.
+//
+// The length is encoded using 1 to 5 bytes depending on its value:
+//
+//     len < 0x80         0xxxxxxx
+//     len < 0x4000       10xxxxxx xxxxxxxx
+//     len < 0x200000     110xxxxx xxxxxxxx xxxxxxxx
+//     len < 0x10000000   1110xxxx xxxxxxxx xxxxxxxx xxxxxxxx
+//     len <= MAX_WORD_LEN 11110000 xxxxxxxx xxxxxxxx xxxxxxxx xxxxxxxx
+//
+// API documentation:
+//     http://wiki.mikrotik.com/wiki/Manual:API#API_words
+//
+pub fn encode_len(len: usize, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+    if len < 0x80 {
+        buf.push(len as u8);
+    } else if len < 0x4000 {
+        let len = len | 0x8000;
+        buf.extend_from_slice(&[(len >> 8) as u8, len as u8]);
+    } else if len < 0x200000 {
+        let len = len | 0xC00000;
+        buf.extend_from_slice(&[(len >> 16) as u8, (len >> 8) as u8, len as u8]);
+    } else if len < 0x10000000 {
+        let len = len | 0xE0000000;
+        buf.extend_from_slice(&[(len >> 24) as u8, (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+    } else if len <= MAX_WORD_LEN {
+        buf.extend_from_slice(&[0xF0,
+                                (len >> 24) as u8,
+                                (len >> 16) as u8,
+                                (len >> 8) as u8,
+                                len as u8]);
+    } else {
+        return Err(CodecError::WordTooLong(len));
+    }
+
+    Ok(())
+}
+
+// Decodes the length prefix at the start of 'buf'.
+//
+// Returns the decoded length together with the amount of bytes used by the prefix,
+// or None if 'buf' does not contain the complete prefix yet.
+pub fn decode_len(buf: &[u8]) -> Result<Option<(usize, usize)>, CodecError> {
+    if buf.is_empty() {
+        return Ok(None);
+    }
+
+    let first = buf[0];
+    let (prefix_len, mut len) = if first & 0x80 == 0x00 {
+        (1, first as usize)
+    } else if first & 0xC0 == 0x80 {
+        (2, (first & !0xC0) as usize)
+    } else if first & 0xE0 == 0xC0 {
+        (3, (first & !0xE0) as usize)
+    } else if first & 0xF0 == 0xE0 {
+        (4, (first & !0xF0) as usize)
+    } else if first & 0xF8 == 0xF0 {
+        (5, 0)
+    } else {
+        return Err(CodecError::ReservedControlByte(first));
+    };
+
+    if buf.len() < prefix_len {
+        return Ok(None);
+    }
+
+    for b in &buf[1..prefix_len] {
+        len = (len << 8) | *b as usize;
+    }
+
+    Ok(Some((len, prefix_len)))
+}
+
+// Appends 'word' with its length prefix to 'buf'.
+pub fn encode_word(word: &[u8], buf: &mut Vec<u8>) -> Result<(), CodecError> {
+    encode_len(word.len(), buf)?;
+    buf.extend_from_slice(word);
+
+    Ok(())
+}
+
+// Decodes the word at the start of 'buf'.
+//
+// Returns the word together with the total amount of bytes consumed from 'buf', or
+// None if 'buf' does not contain the complete word yet.
+pub fn decode_word(buf: &[u8]) -> Result<Option<(Vec<u8>, usize)>, CodecError> {
+    let (len, prefix_len) = match decode_len(buf)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    if buf.len() - prefix_len < len {
+        return Ok(None);
+    }
+
+    let end = prefix_len + len;
+    Ok(Some((buf[prefix_len..end].to_vec(), end)))
+}
+
+// Appends every word of the sentence followed by the zero length word that
+// terminates it to 'buf'.
+pub fn encode_sentence<W: AsRef<[u8]>>(words: &[W], buf: &mut Vec<u8>) -> Result<(), CodecError> {
+    for w in words {
+        encode_word(w.as_ref(), buf)?;
+    }
+
+    buf.push(0);
+
+    Ok(())
+}
+
+// Decoder incrementally decodes sentences from bytes received in arbitrary chunks.
+//
+// Bytes are handed to the decoder with 'feed' as they arrive from the transport and
+// complete sentences are pulled out with 'decode_sentence'. Words of a sentence that
+// has not been terminated yet are kept between calls.
+pub struct Decoder {
+    buffer: Vec<u8>,
+    words: Vec<Vec<u8>>,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder {
+    pub fn new() -> Decoder {
+        Decoder {
+            buffer: Vec::new(),
+            words: Vec::new(),
+        }
+    }
+
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    // Returns true if there are no buffered bytes nor words from an unfinished
+    // sentence.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.words.is_empty()
+    }
+
+    // Returns the next complete sentence, or None if more bytes are needed.
+    //
+    // A sentence made only of the terminating zero length word is returned as an
+    // empty Vec.
+    pub fn decode_sentence(&mut self) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
+        let mut consumed = 0;
+        let mut sentence = None;
+
+        while let Some((word, n)) = decode_word(&self.buffer[consumed..])? {
+            consumed += n;
+
+            if word.is_empty() {
+                sentence = Some(self.words.drain(..).collect());
+                break;
+            }
+
+            self.words.push(word);
+        }
+
+        self.buffer.drain(..consumed);
+
+        Ok(sentence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::CodecError;
+
+    #[test]
+    fn test_encode_len_widths() {
+        let cases: Vec<(usize, Vec<u8>)> = vec![(0x00, vec![0x00]),
+                                                (0x7F, vec![0x7F]),
+                                                (0x80, vec![0x80, 0x80]),
+                                                (0x3FFF, vec![0xBF, 0xFF]),
+                                                (0x4000, vec![0xC0, 0x40, 0x00]),
+                                                (0x1FFFFF, vec![0xDF, 0xFF, 0xFF]),
+                                                (0x200000, vec![0xE0, 0x20, 0x00, 0x00]),
+                                                (0xFFFFFFF, vec![0xEF, 0xFF, 0xFF, 0xFF]),
+                                                (0x10000000,
+                                                 vec![0xF0, 0x10, 0x00, 0x00, 0x00]),
+                                                (MAX_WORD_LEN,
+                                                 vec![0xF0, 0xFF, 0xFF, 0xFF, 0xFF])];
+
+        for (len, expected) in cases {
+            let mut buf = Vec::new();
+            encode_len(len, &mut buf).expect("length should be encodable");
+
+            assert_eq!(buf, expected);
+            assert_eq!(decode_len(&buf).unwrap(), Some((len, expected.len())));
+        }
+    }
+
+    #[test]
+    fn test_encode_len_too_long() {
+        if MAX_WORD_LEN == usize::MAX {
+            return;
+        }
+
+        let mut buf = Vec::new();
+        let error = encode_len(MAX_WORD_LEN + 1, &mut buf).unwrap_err();
+
+        assert_eq!(error, CodecError::WordTooLong(MAX_WORD_LEN + 1));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_decode_len_partial_prefix() {
+        assert_eq!(decode_len(&[]).unwrap(), None);
+        assert_eq!(decode_len(&[0x80]).unwrap(), None);
+        assert_eq!(decode_len(&[0xC0, 0x40]).unwrap(), None);
+        assert_eq!(decode_len(&[0xF0, 0x10, 0x00, 0x00]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_len_reserved_control_bytes() {
+        for b in 0xF8..0x100 {
+            let error = decode_len(&[b as u8, 0, 0, 0, 0]).unwrap_err();
+
+            assert_eq!(error, CodecError::ReservedControlByte(b as u8));
+        }
+    }
+
+    #[test]
+    fn test_word_round_trip() {
+        for len in [0, 1, 0x7F, 0x80, 0x3FFF, 0x4000, 0x20000] {
+            let word = vec![b'a'; len];
+            let mut buf = Vec::new();
+            encode_word(&word, &mut buf).unwrap();
+
+            let (decoded, consumed) = decode_word(&buf).unwrap().expect("complete word");
+            assert_eq!(decoded, word);
+            assert_eq!(consumed, buf.len());
+
+            assert_eq!(decode_word(&buf[..buf.len() - 1]).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_encode_sentence() {
+        let mut buf = Vec::new();
+        encode_sentence(&["/login", "=name=admin"], &mut buf).unwrap();
+
+        let mut expected = vec![6];
+        expected.extend_from_slice(b"/login");
+        expected.push(11);
+        expected.extend_from_slice(b"=name=admin");
+        expected.push(0);
+
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_decoder_byte_by_byte() {
+        let long_word = vec![b'x'; 0x4000];
+        let mut buf = Vec::new();
+        encode_sentence(&[b"!re".to_vec(), long_word.clone()], &mut buf).unwrap();
+        encode_sentence(&["!done"], &mut buf).unwrap();
+
+        let mut decoder = Decoder::new();
+        let mut sentences = Vec::new();
+
+        for b in &buf {
+            decoder.feed(&[*b]);
+
+            if let Some(s) = decoder.decode_sentence().unwrap() {
+                sentences.push(s);
+            }
+        }
+
+        assert_eq!(sentences,
+                   vec![vec![b"!re".to_vec(), long_word], vec![b"!done".to_vec()]]);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_decoder_multiple_sentences_in_one_chunk() {
+        let mut buf = Vec::new();
+        encode_sentence(&["!re", "=.id=*1"], &mut buf).unwrap();
+        encode_sentence::<&str>(&[], &mut buf).unwrap();
+        encode_sentence(&["!done"], &mut buf).unwrap();
+
+        let mut decoder = Decoder::new();
+        decoder.feed(&buf);
+
+        assert_eq!(decoder.decode_sentence().unwrap(),
+                   Some(vec![b"!re".to_vec(), b"=.id=*1".to_vec()]));
+        assert_eq!(decoder.decode_sentence().unwrap(), Some(vec![]));
+        assert_eq!(decoder.decode_sentence().unwrap(),
+                   Some(vec![b"!done".to_vec()]));
+        assert_eq!(decoder.decode_sentence().unwrap(), None);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_decoder_keeps_unfinished_sentence() {
+        let mut buf = Vec::new();
+        encode_sentence(&["!re", "=name=ether1"], &mut buf).unwrap();
+
+        let mut decoder = Decoder::new();
+        decoder.feed(&buf[..5]);
+        assert_eq!(decoder.decode_sentence().unwrap(), None);
+        assert!(!decoder.is_empty());
+
+        decoder.feed(&buf[5..]);
+        assert_eq!(decoder.decode_sentence().unwrap(),
+                   Some(vec![b"!re".to_vec(), b"=name=ether1".to_vec()]));
+    }
+
+    #[test]
+    fn test_decoder_reserved_control_byte() {
+        let mut decoder = Decoder::new();
+        decoder.feed(&[3, b'!', b'r', b'e', 0xF8]);
+
+        assert_eq!(decoder.decode_sentence().unwrap_err(),
+                   CodecError::ReservedControlByte(0xF8));
+    }
+}
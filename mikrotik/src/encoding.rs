@@ -0,0 +1,154 @@
+// Encoding is the character set the router stores text in.
+//
+// The API carries words as plain bytes and RouterOS does not convert them, so names
+// and comments entered through WinBox arrive in the code page of the computer they
+// were typed on. Bytes that are not valid in the encoding are decoded with a
+// replacement character, and characters it can not represent are sent as '?'.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Latin1,
+    Windows1251,
+    Windows1252,
+}
+
+impl Encoding {
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match *self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Encoding::Latin1 => bytes.iter().map(|b| *b as char).collect(),
+            Encoding::Windows1251 => decode_table(bytes, &WINDOWS_1251),
+            Encoding::Windows1252 => decode_table(bytes, &WINDOWS_1252),
+        }
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match *self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Latin1 => {
+                text.chars()
+                    .map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' })
+                    .collect()
+            }
+            Encoding::Windows1251 => encode_table(text, &WINDOWS_1251),
+            Encoding::Windows1252 => encode_table(text, &WINDOWS_1252),
+        }
+    }
+
+    pub(crate) fn decode_words(&self, words: &[Vec<u8>]) -> Vec<String> {
+        words.iter().map(|w| self.decode(w)).collect()
+    }
+
+    pub(crate) fn encode_words(&self, words: &[String]) -> Vec<Vec<u8>> {
+        words.iter().map(|w| self.encode(w)).collect()
+    }
+}
+
+// Decodes 'bytes' with a single byte code page that matches ASCII in its lower half
+// and maps the upper half to 'table'.
+fn decode_table(bytes: &[u8], table: &[char; 128]) -> String {
+    bytes.iter()
+        .map(|b| if *b < 0x80 { *b as char } else { table[*b as usize - 0x80] })
+        .collect()
+}
+
+fn encode_table(text: &str, table: &[char; 128]) -> Vec<u8> {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii() {
+                return c as u8;
+            }
+
+            match table.iter().position(|t| *t == c) {
+                Some(i) => (0x80 + i) as u8,
+                None => b'?',
+            }
+        })
+        .collect()
+}
+
+// Upper halves of the Windows code pages. The few bytes left undefined by Microsoft
+// map to the C1 control character with the same value, so they still round-trip.
+const WINDOWS_1251: [char; 128] = [
+    '\u{0402}', '\u{0403}', '\u{201A}', '\u{0453}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{20AC}', '\u{2030}', '\u{0409}', '\u{2039}', '\u{040A}', '\u{040C}', '\u{040B}', '\u{040F}',
+    '\u{0452}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{0098}', '\u{2122}', '\u{0459}', '\u{203A}', '\u{045A}', '\u{045C}', '\u{045B}', '\u{045F}',
+    '\u{00A0}', '\u{040E}', '\u{045E}', '\u{0408}', '\u{00A4}', '\u{0490}', '\u{00A6}', '\u{00A7}',
+    '\u{0401}', '\u{00A9}', '\u{0404}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{0407}',
+    '\u{00B0}', '\u{00B1}', '\u{0406}', '\u{0456}', '\u{0491}', '\u{00B5}', '\u{00B6}', '\u{00B7}',
+    '\u{0451}', '\u{2116}', '\u{0454}', '\u{00BB}', '\u{0458}', '\u{0405}', '\u{0455}', '\u{0457}',
+    '\u{0410}', '\u{0411}', '\u{0412}', '\u{0413}', '\u{0414}', '\u{0415}', '\u{0416}', '\u{0417}',
+    '\u{0418}', '\u{0419}', '\u{041A}', '\u{041B}', '\u{041C}', '\u{041D}', '\u{041E}', '\u{041F}',
+    '\u{0420}', '\u{0421}', '\u{0422}', '\u{0423}', '\u{0424}', '\u{0425}', '\u{0426}', '\u{0427}',
+    '\u{0428}', '\u{0429}', '\u{042A}', '\u{042B}', '\u{042C}', '\u{042D}', '\u{042E}', '\u{042F}',
+    '\u{0430}', '\u{0431}', '\u{0432}', '\u{0433}', '\u{0434}', '\u{0435}', '\u{0436}', '\u{0437}',
+    '\u{0438}', '\u{0439}', '\u{043A}', '\u{043B}', '\u{043C}', '\u{043D}', '\u{043E}', '\u{043F}',
+    '\u{0440}', '\u{0441}', '\u{0442}', '\u{0443}', '\u{0444}', '\u{0445}', '\u{0446}', '\u{0447}',
+    '\u{0448}', '\u{0449}', '\u{044A}', '\u{044B}', '\u{044C}', '\u{044D}', '\u{044E}', '\u{044F}',
+];
+
+const WINDOWS_1252: [char; 128] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+    '\u{00A0}', '\u{00A1}', '\u{00A2}', '\u{00A3}', '\u{00A4}', '\u{00A5}', '\u{00A6}', '\u{00A7}',
+    '\u{00A8}', '\u{00A9}', '\u{00AA}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{00AF}',
+    '\u{00B0}', '\u{00B1}', '\u{00B2}', '\u{00B3}', '\u{00B4}', '\u{00B5}', '\u{00B6}', '\u{00B7}',
+    '\u{00B8}', '\u{00B9}', '\u{00BA}', '\u{00BB}', '\u{00BC}', '\u{00BD}', '\u{00BE}', '\u{00BF}',
+    '\u{00C0}', '\u{00C1}', '\u{00C2}', '\u{00C3}', '\u{00C4}', '\u{00C5}', '\u{00C6}', '\u{00C7}',
+    '\u{00C8}', '\u{00C9}', '\u{00CA}', '\u{00CB}', '\u{00CC}', '\u{00CD}', '\u{00CE}', '\u{00CF}',
+    '\u{00D0}', '\u{00D1}', '\u{00D2}', '\u{00D3}', '\u{00D4}', '\u{00D5}', '\u{00D6}', '\u{00D7}',
+    '\u{00D8}', '\u{00D9}', '\u{00DA}', '\u{00DB}', '\u{00DC}', '\u{00DD}', '\u{00DE}', '\u{00DF}',
+    '\u{00E0}', '\u{00E1}', '\u{00E2}', '\u{00E3}', '\u{00E4}', '\u{00E5}', '\u{00E6}', '\u{00E7}',
+    '\u{00E8}', '\u{00E9}', '\u{00EA}', '\u{00EB}', '\u{00EC}', '\u{00ED}', '\u{00EE}', '\u{00EF}',
+    '\u{00F0}', '\u{00F1}', '\u{00F2}', '\u{00F3}', '\u{00F4}', '\u{00F5}', '\u{00F6}', '\u{00F7}',
+    '\u{00F8}', '\u{00F9}', '\u{00FA}', '\u{00FB}', '\u{00FC}', '\u{00FD}', '\u{00FE}', '\u{00FF}',
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8() {
+        let text = "Jürgen Müller, Иван";
+
+        assert_eq!(Encoding::Utf8.decode(&Encoding::Utf8.encode(text)), text);
+        assert_eq!(Encoding::Utf8.decode(&[b'a', 0xFF]), "a\u{FFFD}");
+    }
+
+    #[test]
+    fn test_latin1() {
+        assert_eq!(Encoding::Latin1.encode("Müller"), b"M\xFCller");
+        assert_eq!(Encoding::Latin1.decode(b"M\xFCller"), "Müller");
+        assert_eq!(Encoding::Latin1.encode("€"), b"?");
+    }
+
+    #[test]
+    fn test_windows_1251() {
+        let bytes = b"\xC8\xE2\xE0\xED \xCF\xE5\xF2\xF0\xEE\xE2 \xA8\xB8";
+
+        assert_eq!(Encoding::Windows1251.decode(bytes), "Иван Петров Ёё");
+        assert_eq!(Encoding::Windows1251.encode("Иван Петров Ёё"), bytes);
+        assert_eq!(Encoding::Windows1251.encode("é"), b"?");
+    }
+
+    #[test]
+    fn test_windows_1252() {
+        assert_eq!(Encoding::Windows1252.decode(b"\x80 Fran\xE7ois \x9C"), "€ François œ");
+        assert_eq!(Encoding::Windows1252.encode("€ François œ"), b"\x80 Fran\xE7ois \x9C");
+        assert_eq!(Encoding::Windows1252.encode("Ж"), b"?");
+    }
+
+    #[test]
+    fn test_every_byte_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+
+        for encoding in [Encoding::Latin1, Encoding::Windows1251, Encoding::Windows1252] {
+            assert_eq!(encoding.encode(&encoding.decode(&bytes)), bytes);
+        }
+    }
+}
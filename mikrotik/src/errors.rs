@@ -1,8 +1,12 @@
-use std::net;
 use std::io;
 use std::error::Error;
 use std::fmt::{self, Display};
 
+#[cfg(feature = "tls")]
+use openssl::error::ErrorStack;
+#[cfg(feature = "tls")]
+use openssl::ssl;
+
 
 // UnhexlifyError describes an error when trying to convert a string to a Vec<u8>.
 #[derive(Debug, PartialEq)]
@@ -11,19 +15,17 @@ pub enum UnhexlifyError {
     InvalidHexDigit(char),
 }
 
-impl Error for UnhexlifyError {
+impl UnhexlifyError {
     fn description(&self) -> &str {
         match *self {
             UnhexlifyError::OddCharacterCount(_) => "invalid string length",
             UnhexlifyError::InvalidHexDigit(_) => "could not convert character to hex digit",
         }
     }
-
-    fn cause(&self) -> Option<&Error> {
-        Some(self as &Error)
-    }
 }
 
+impl Error for UnhexlifyError {}
+
 impl Display for UnhexlifyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -38,72 +40,164 @@ impl Display for UnhexlifyError {
 }
 
 
+// CodecError describes an error when encoding or decoding API words.
+//
+// ReservedControlByte happens when a length prefix starts with one of the control
+// bytes (0xF8 - 0xFF) reserved by RouterOS.
+//
+// WordTooLong is returned when a word is longer than what a length prefix can
+// describe.
+#[derive(Debug, PartialEq)]
+pub enum CodecError {
+    ReservedControlByte(u8),
+    WordTooLong(usize),
+}
+
+impl CodecError {
+    fn description(&self) -> &str {
+        match *self {
+            CodecError::ReservedControlByte(_) => "reserved control byte in length prefix",
+            CodecError::WordTooLong(_) => "word exceeds the maximum encodable length",
+        }
+    }
+}
+
+impl Error for CodecError {}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodecError::ReservedControlByte(b) => {
+                write!(f, "control byte '{:#X}': {}", b, self.description())
+            }
+            CodecError::WordTooLong(len) => {
+                write!(f, "word length '{}': {}", len, self.description())
+            }
+        }
+    }
+}
+
+impl From<CodecError> for io::Error {
+    fn from(err: CodecError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+
 // CreateClientError is used to describe the different types of errors when trying to
 // create a Mikrotik Client.
 //
-// AddrParseError happens when trying to parse a string to IPv4.
+// IoError is returned when the address of the router could not be resolved or there
+// was an error creating the inner stream socket used for communication with it.
 //
-// IoError is returned when there was an error creating the inner stream socket used
-// for communication with the router.
+// TlsError happens when the TLS session with the api-ssl service could not be
+// configured or established.
+//
+// Timeout is returned when the router did not accept the connection or complete the
+// TLS handshake within the timeouts set with ClientBuilder.
 #[derive(Debug)]
 pub enum CreateClientError {
-    AddrParseError(net::AddrParseError),
     IoError(io::Error),
+    #[cfg(feature = "tls")]
+    TlsError(ssl::Error),
+    Timeout,
 }
 
 impl Error for CreateClientError {
-    fn description(&self) -> &str {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self {
-            CreateClientError::AddrParseError(ref e) => e.description(),
-            CreateClientError::IoError(ref e) => e.description(),
+            CreateClientError::IoError(ref e) => Some(e),
+            #[cfg(feature = "tls")]
+            CreateClientError::TlsError(ref e) => Some(e),
+            CreateClientError::Timeout => None,
         }
     }
-
-    fn cause(&self) -> Option<&Error> {
-        Some(match *self {
-            CreateClientError::AddrParseError(ref e) => e,
-            CreateClientError::IoError(ref e) => e,
-        })
-    }
 }
 
 impl Display for CreateClientError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            CreateClientError::AddrParseError(ref e) => e.fmt(f),
             CreateClientError::IoError(ref e) => e.fmt(f),
+            #[cfg(feature = "tls")]
+            CreateClientError::TlsError(ref e) => e.fmt(f),
+            CreateClientError::Timeout => write!(f, "timed out connecting to the router"),
         }
     }
 }
 
+// Returns true if 'err' comes from a socket timeout. Reads that time out fail with
+// WouldBlock on Unix and with TimedOut on Windows.
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+}
+
 impl From<io::Error> for CreateClientError {
     fn from(err: io::Error) -> CreateClientError {
+        if is_timeout(&err) {
+            return CreateClientError::Timeout;
+        }
+
         CreateClientError::IoError(err)
     }
 }
 
-impl From<net::AddrParseError> for CreateClientError {
-    fn from(err: net::AddrParseError) -> CreateClientError {
-        CreateClientError::AddrParseError(err)
+#[cfg(feature = "tls")]
+impl From<ssl::Error> for CreateClientError {
+    fn from(err: ssl::Error) -> CreateClientError {
+        CreateClientError::TlsError(err)
+    }
+}
+
+#[cfg(feature = "tls")]
+impl From<ErrorStack> for CreateClientError {
+    fn from(err: ErrorStack) -> CreateClientError {
+        CreateClientError::TlsError(err.into())
+    }
+}
+
+#[cfg(feature = "tls")]
+impl<S> From<ssl::HandshakeError<S>> for CreateClientError {
+    fn from(err: ssl::HandshakeError<S>) -> CreateClientError {
+        match err {
+            ssl::HandshakeError::SetupFailure(e) => e.into(),
+            ssl::HandshakeError::Failure(s) | ssl::HandshakeError::WouldBlock(s) => {
+                let err = s.into_error();
+                match err.io_error() {
+                    Some(e) if is_timeout(e) => CreateClientError::Timeout,
+                    _ => CreateClientError::TlsError(err),
+                }
+            }
+        }
     }
 }
 
 
 // MikroTik Error.
+//
+// UnexpectedReply is returned when the router answers with a reply that is missing
+// something the command requires, such as the challenge of the legacy login.
+//
+// Timeout is returned when the router does not answer within the read timeout or the
+// command timeout set with ClientBuilder. Its reply may still arrive later, so the
+// client should not be used for further commands.
 #[derive(Debug)]
 pub enum MikrotikError {
     UnhexlifyError(UnhexlifyError),
     IoError(io::Error),
     Fatal(String),
     Trap { category: u8, msg: String },
+    UnexpectedReply(String),
+    Timeout,
 }
 
-impl Error for MikrotikError {
+impl MikrotikError {
     fn description(&self) -> &str {
         match *self {
-            MikrotikError::UnhexlifyError(ref e) => e.description(),
-            MikrotikError::IoError(ref e) => e.description(),
+            MikrotikError::UnhexlifyError(_) => "could not parse login challenge",
+            MikrotikError::IoError(_) => "communication with the router failed",
             MikrotikError::Fatal(_) => "a fatal error has ocurred",
+            MikrotikError::UnexpectedReply(_) => "unexpected reply from the router",
+            MikrotikError::Timeout => "timed out waiting for the router",
             MikrotikError::Trap { category, .. } => {
                 match category {
                     0 => "missing item or command",
@@ -119,13 +213,15 @@ impl Error for MikrotikError {
             }
         }
     }
+}
 
-    fn cause(&self) -> Option<&Error> {
-        Some(match *self {
-            MikrotikError::UnhexlifyError(ref e) => e,
-            MikrotikError::IoError(ref e) => e,
-            _ => self as &Error,
-        })
+impl Error for MikrotikError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            MikrotikError::UnhexlifyError(ref e) => Some(e),
+            MikrotikError::IoError(ref e) => Some(e),
+            _ => None,
+        }
     }
 }
 
@@ -135,15 +231,23 @@ impl Display for MikrotikError {
             MikrotikError::UnhexlifyError(ref e) => e.fmt(f),
             MikrotikError::IoError(ref e) => e.fmt(f),
             MikrotikError::Fatal(ref msg) => write!(f, "{}: {}", self.description(), msg),
+            MikrotikError::UnexpectedReply(ref msg) => {
+                write!(f, "{}: {}", self.description(), msg)
+            }
             MikrotikError::Trap { category, ref msg } => {
                 write!(f, "[trap_id:{}] {} - {}", category, self.description(), msg)
             }
+            MikrotikError::Timeout => write!(f, "{}", self.description()),
         }
     }
 }
 
 impl From<io::Error> for MikrotikError {
     fn from(err: io::Error) -> MikrotikError {
+        if is_timeout(&err) {
+            return MikrotikError::Timeout;
+        }
+
         MikrotikError::IoError(err)
     }
 }
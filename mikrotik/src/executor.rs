@@ -0,0 +1,565 @@
+use crate::client;
+use crate::errors::MikrotikError;
+use crate::models;
+use crate::query::Query;
+use crate::resource::{self, Resource};
+use crate::sentence::Sentence;
+use crate::types::MacAddress;
+
+// Parser builds the result of a command from all its replies, ending with the '!done'
+// one.
+pub type Parser<R> = fn(&[Sentence]) -> Result<R, MikrotikError>;
+
+// Executor holds the commands every client can run, so each of them is written once
+// for &mut Client, &Multiplexer and &mut AsyncClient. Its methods return the result
+// of the command on the blocking clients, and a future resolving to it on
+// AsyncClient:
+//
+//     use mikrotik::Executor;
+//
+//     let entries = client.get_address_list()?;
+//     let entries = async_client.get_address_list().await?;
+pub trait Executor: Sized {
+    // Either Result<R, MikrotikError> or a future resolving to it.
+    type Output<R>;
+
+    // Sends 'sentence' and returns what 'parse' builds from its replies. If building
+    // the sentence failed, its error is returned without sending anything.
+    fn run<R>(self,
+              sentence: Result<Vec<String>, MikrotikError>,
+              parse: Parser<R>)
+              -> Self::Output<R>;
+
+    // Runs the print command of 'menu', such as "/ip/address", returning only the
+    // items matched by 'query' with the properties it selects.
+    fn print_where(self, menu: &str, query: &Query) -> Self::Output<Vec<Sentence>> {
+        self.run(Ok(query.sentence(&client::print_command(menu))),
+                 |response| Ok(response.to_vec()))
+    }
+
+    // Returns the items of the menu of T matched by 'query'.
+    fn print_items<T: Resource>(self, query: &Query) -> Self::Output<Vec<T>> {
+        self.run(Ok(resource::print_sentence::<T>(query)), resource::parse_items)
+    }
+
+    // Adds 'item' to its menu and returns the id the router gave it.
+    fn add<T: Resource>(self, item: &T) -> Self::Output<String> {
+        self.run(Ok(resource::add_sentence(item)), resource::added_id)
+    }
+
+    // Updates the item with the id of 'item' to match it.
+    fn set<T: Resource>(self, item: &T) -> Self::Output<()> {
+        self.run(resource::set_sentence(item), done)
+    }
+
+    // Removes the item with 'id' from the menu of T.
+    fn remove<T: Resource>(self, id: &str) -> Self::Output<()> {
+        self.run(Ok(resource::item_sentence::<T>("remove", id)), done)
+    }
+
+    fn enable<T: Resource>(self, id: &str) -> Self::Output<()> {
+        self.run(Ok(resource::item_sentence::<T>("enable", id)), done)
+    }
+
+    fn disable<T: Resource>(self, id: &str) -> Self::Output<()> {
+        self.run(Ok(resource::item_sentence::<T>("disable", id)), done)
+    }
+
+    // Moves the item with 'id' before the one with 'before', or to the end of the menu
+    // of T when it is None. Items are matched in order in menus such as the firewall
+    // ones.
+    fn move_item<T: Resource>(self, id: &str, before: Option<&str>) -> Self::Output<()> {
+        self.run(Ok(resource::move_sentence::<T>(id, before)), done)
+    }
+
+    fn get_address_list(self) -> Self::Output<Vec<models::IPAddress>> {
+        self.get_address_list_where(&Query::new())
+    }
+
+    fn get_address_list_where(self, query: &Query) -> Self::Output<Vec<models::IPAddress>> {
+        self.print_items(query)
+    }
+
+    // Returns the entry for 'address' in 'list', if any.
+    fn find_address(self, list: &str, address: &str) -> Self::Output<Option<models::IPAddress>> {
+        let query = client::address_query(list, address);
+
+        self.run(Ok(resource::print_sentence::<models::IPAddress>(&query)), first_item)
+    }
+
+    // Adds 'entry' to the address list and returns the id the router gave it.
+    fn add_address(self, entry: &models::IPAddress) -> Self::Output<String> {
+        self.add(entry)
+    }
+
+    // Updates 'entry' without its 'timeout'. The timeout read back from the router is
+    // the time left, and sending it would restart the countdown from that value. Use
+    // Executor::set to change the timeout of an entry.
+    fn set_address(self, entry: &models::IPAddress) -> Self::Output<()> {
+        self.set(&models::IPAddress { timeout: None, ..entry.clone() })
+    }
+
+    fn remove_address(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::IPAddress>(id)
+    }
+
+    fn get_queue_list(self) -> Self::Output<Vec<models::Client>> {
+        self.get_queue_list_where(&Query::new())
+    }
+
+    fn get_queue_list_where(self, query: &Query) -> Self::Output<Vec<models::Client>> {
+        self.print_items(query)
+    }
+
+    // Returns the simple queue called 'name', if any.
+    fn find_queue(self, name: &str) -> Self::Output<Option<models::Client>> {
+        self.run(Ok(resource::print_sentence::<models::Client>(&name_query(name))), first_item)
+    }
+
+    // Adds the simple queue 'queue' and returns the id the router gave it.
+    fn add_queue(self, queue: &models::Client) -> Self::Output<String> {
+        self.add(queue)
+    }
+
+    fn set_queue(self, queue: &models::Client) -> Self::Output<()> {
+        self.set(queue)
+    }
+
+    fn remove_queue(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::Client>(id)
+    }
+
+    fn enable_queue(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::Client>(id)
+    }
+
+    fn disable_queue(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::Client>(id)
+    }
+
+    fn get_queue_trees(self) -> Self::Output<Vec<models::QueueTree>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the queue tree entry called 'name', if any.
+    fn find_queue_tree(self, name: &str) -> Self::Output<Option<models::QueueTree>> {
+        self.run(Ok(resource::print_sentence::<models::QueueTree>(&name_query(name))),
+                 first_item)
+    }
+
+    // Adds the queue tree entry 'entry' and returns the id the router gave it.
+    fn add_queue_tree(self, entry: &models::QueueTree) -> Self::Output<String> {
+        self.add(entry)
+    }
+
+    fn set_queue_tree(self, entry: &models::QueueTree) -> Self::Output<()> {
+        self.set(entry)
+    }
+
+    fn remove_queue_tree(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::QueueTree>(id)
+    }
+
+    fn enable_queue_tree(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::QueueTree>(id)
+    }
+
+    fn disable_queue_tree(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::QueueTree>(id)
+    }
+
+    fn get_queue_types(self) -> Self::Output<Vec<models::QueueType>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the queue type called 'name', if any.
+    fn find_queue_type(self, name: &str) -> Self::Output<Option<models::QueueType>> {
+        self.run(Ok(resource::print_sentence::<models::QueueType>(&name_query(name))),
+                 first_item)
+    }
+
+    // Adds the queue type 'queue_type' and returns the id the router gave it.
+    fn add_queue_type(self, queue_type: &models::QueueType) -> Self::Output<String> {
+        self.add(queue_type)
+    }
+
+    fn set_queue_type(self, queue_type: &models::QueueType) -> Self::Output<()> {
+        self.set(queue_type)
+    }
+
+    fn remove_queue_type(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::QueueType>(id)
+    }
+
+    fn get_interfaces(self) -> Self::Output<Vec<models::Interface>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the interface called 'name', if any.
+    fn find_interface(self, name: &str) -> Self::Output<Option<models::Interface>> {
+        self.run(Ok(resource::print_sentence::<models::Interface>(&name_query(name))),
+                 first_item)
+    }
+
+    fn set_interface(self, interface: &models::Interface) -> Self::Output<()> {
+        self.set(interface)
+    }
+
+    fn enable_interface(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::Interface>(id)
+    }
+
+    fn disable_interface(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::Interface>(id)
+    }
+
+    fn get_dhcp_leases(self) -> Self::Output<Vec<models::DhcpLease>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the lease of the client with 'mac_address', if any.
+    fn find_dhcp_lease(self, mac_address: &MacAddress) -> Self::Output<Option<models::DhcpLease>> {
+        let query = Query::new().equal("mac-address", &mac_address.to_string());
+
+        self.run(Ok(resource::print_sentence::<models::DhcpLease>(&query)), first_item)
+    }
+
+    // Adds the static lease 'lease' and returns the id the router gave it.
+    fn add_dhcp_lease(self, lease: &models::DhcpLease) -> Self::Output<String> {
+        self.add(lease)
+    }
+
+    fn set_dhcp_lease(self, lease: &models::DhcpLease) -> Self::Output<()> {
+        self.set(lease)
+    }
+
+    // Turns the dynamic lease with 'id' into a static one, keeping its address for the
+    // client.
+    fn make_dhcp_lease_static(self, id: &str) -> Self::Output<()> {
+        self.run(Ok(resource::item_sentence::<models::DhcpLease>("make-static", id)), done)
+    }
+
+    fn remove_dhcp_lease(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::DhcpLease>(id)
+    }
+
+    fn enable_dhcp_lease(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::DhcpLease>(id)
+    }
+
+    fn disable_dhcp_lease(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::DhcpLease>(id)
+    }
+
+    fn get_dhcp_servers(self) -> Self::Output<Vec<models::DhcpServer>> {
+        self.print_items(&Query::new())
+    }
+
+    fn get_dhcp_networks(self) -> Self::Output<Vec<models::DhcpNetwork>> {
+        self.print_items(&Query::new())
+    }
+
+    fn get_ppp_secrets(self) -> Self::Output<Vec<models::PppSecret>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the PPP secret called 'name', if any.
+    fn find_ppp_secret(self, name: &str) -> Self::Output<Option<models::PppSecret>> {
+        self.run(Ok(resource::print_sentence::<models::PppSecret>(&name_query(name))),
+                 first_item)
+    }
+
+    // Adds the PPP secret 'secret' and returns the id the router gave it.
+    fn add_ppp_secret(self, secret: &models::PppSecret) -> Self::Output<String> {
+        self.add(secret)
+    }
+
+    fn set_ppp_secret(self, secret: &models::PppSecret) -> Self::Output<()> {
+        self.set(secret)
+    }
+
+    fn remove_ppp_secret(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::PppSecret>(id)
+    }
+
+    fn enable_ppp_secret(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::PppSecret>(id)
+    }
+
+    fn disable_ppp_secret(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::PppSecret>(id)
+    }
+
+    fn get_ppp_active(self) -> Self::Output<Vec<models::PppActive>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the session of the PPP client 'name', if it is connected.
+    fn find_ppp_active(self, name: &str) -> Self::Output<Option<models::PppActive>> {
+        self.run(Ok(resource::print_sentence::<models::PppActive>(&name_query(name))),
+                 first_item)
+    }
+
+    // Disconnects the PPP session with 'id'. The client can connect again
+    // unless its secret is disabled first.
+    fn disconnect_ppp(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::PppActive>(id)
+    }
+
+    fn get_ppp_profiles(self) -> Self::Output<Vec<models::PppProfile>> {
+        self.print_items(&Query::new())
+    }
+
+    fn get_hotspot_users(self) -> Self::Output<Vec<models::HotspotUser>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the hotspot user called 'name', if any.
+    fn find_hotspot_user(self, name: &str) -> Self::Output<Option<models::HotspotUser>> {
+        self.run(Ok(resource::print_sentence::<models::HotspotUser>(&name_query(name))),
+                 first_item)
+    }
+
+    // Adds the hotspot user 'user' and returns the id the router gave it.
+    fn add_hotspot_user(self, user: &models::HotspotUser) -> Self::Output<String> {
+        self.add(user)
+    }
+
+    fn set_hotspot_user(self, user: &models::HotspotUser) -> Self::Output<()> {
+        self.set(user)
+    }
+
+    fn remove_hotspot_user(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::HotspotUser>(id)
+    }
+
+    fn enable_hotspot_user(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::HotspotUser>(id)
+    }
+
+    fn disable_hotspot_user(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::HotspotUser>(id)
+    }
+
+    fn get_hotspot_active(self) -> Self::Output<Vec<models::HotspotActive>> {
+        self.print_items(&Query::new())
+    }
+
+    // Returns the session of the hotspot user 'user', if it is logged in.
+    fn find_hotspot_active(self, user: &str) -> Self::Output<Option<models::HotspotActive>> {
+        let query = Query::new().equal("user", user);
+
+        self.run(Ok(resource::print_sentence::<models::HotspotActive>(&query)), first_item)
+    }
+
+    // Logs out the hotspot session with 'id'.
+    fn kick_hotspot_user(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::HotspotActive>(id)
+    }
+
+    fn get_hotspot_user_profiles(self) -> Self::Output<Vec<models::HotspotUserProfile>> {
+        self.print_items(&Query::new())
+    }
+
+    fn get_filter_rules(self) -> Self::Output<Vec<models::FilterRule>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds 'rule' at the end of its firewall filter chain and returns the id the router
+    // gave it.
+    fn add_filter_rule(self, rule: &models::FilterRule) -> Self::Output<String> {
+        self.add(rule)
+    }
+
+    fn set_filter_rule(self, rule: &models::FilterRule) -> Self::Output<()> {
+        self.set(rule)
+    }
+
+    fn remove_filter_rule(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::FilterRule>(id)
+    }
+
+    fn enable_filter_rule(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::FilterRule>(id)
+    }
+
+    fn disable_filter_rule(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::FilterRule>(id)
+    }
+
+    fn move_filter_rule(self, id: &str, before: Option<&str>) -> Self::Output<()> {
+        self.move_item::<models::FilterRule>(id, before)
+    }
+
+    fn get_nat_rules(self) -> Self::Output<Vec<models::NatRule>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds 'rule' at the end of its NAT chain and returns the id the router gave it.
+    fn add_nat_rule(self, rule: &models::NatRule) -> Self::Output<String> {
+        self.add(rule)
+    }
+
+    fn set_nat_rule(self, rule: &models::NatRule) -> Self::Output<()> {
+        self.set(rule)
+    }
+
+    fn remove_nat_rule(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::NatRule>(id)
+    }
+
+    fn enable_nat_rule(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::NatRule>(id)
+    }
+
+    fn disable_nat_rule(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::NatRule>(id)
+    }
+
+    fn move_nat_rule(self, id: &str, before: Option<&str>) -> Self::Output<()> {
+        self.move_item::<models::NatRule>(id, before)
+    }
+
+    fn get_mangle_rules(self) -> Self::Output<Vec<models::MangleRule>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds 'rule' at the end of its mangle chain and returns the id the router gave it.
+    fn add_mangle_rule(self, rule: &models::MangleRule) -> Self::Output<String> {
+        self.add(rule)
+    }
+
+    fn set_mangle_rule(self, rule: &models::MangleRule) -> Self::Output<()> {
+        self.set(rule)
+    }
+
+    fn remove_mangle_rule(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::MangleRule>(id)
+    }
+
+    fn enable_mangle_rule(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::MangleRule>(id)
+    }
+
+    fn disable_mangle_rule(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::MangleRule>(id)
+    }
+
+    fn move_mangle_rule(self, id: &str, before: Option<&str>) -> Self::Output<()> {
+        self.move_item::<models::MangleRule>(id, before)
+    }
+
+    fn get_ip_addresses(self) -> Self::Output<Vec<models::InterfaceAddress>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds the address 'address' and returns the id the router gave it.
+    fn add_ip_address(self, address: &models::InterfaceAddress) -> Self::Output<String> {
+        self.add(address)
+    }
+
+    fn set_ip_address(self, address: &models::InterfaceAddress) -> Self::Output<()> {
+        self.set(address)
+    }
+
+    fn remove_ip_address(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::InterfaceAddress>(id)
+    }
+
+    fn get_routes(self) -> Self::Output<Vec<models::Route>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds the route 'route' and returns the id the router gave it.
+    fn add_route(self, route: &models::Route) -> Self::Output<String> {
+        self.add(route)
+    }
+
+    fn set_route(self, route: &models::Route) -> Self::Output<()> {
+        self.set(route)
+    }
+
+    fn remove_route(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::Route>(id)
+    }
+
+    fn enable_route(self, id: &str) -> Self::Output<()> {
+        self.enable::<models::Route>(id)
+    }
+
+    fn disable_route(self, id: &str) -> Self::Output<()> {
+        self.disable::<models::Route>(id)
+    }
+
+    fn get_arp_entries(self) -> Self::Output<Vec<models::ArpEntry>> {
+        self.print_items(&Query::new())
+    }
+
+    // Adds the static ARP entry 'entry' and returns the id the router gave it.
+    fn add_arp_entry(self, entry: &models::ArpEntry) -> Self::Output<String> {
+        self.add(entry)
+    }
+
+    fn set_arp_entry(self, entry: &models::ArpEntry) -> Self::Output<()> {
+        self.set(entry)
+    }
+
+    fn remove_arp_entry(self, id: &str) -> Self::Output<()> {
+        self.remove::<models::ArpEntry>(id)
+    }
+
+    // Returns the CPU, memory and disk usage, uptime and version of the router.
+    fn system_resource(self) -> Self::Output<models::SystemResource> {
+        self.run(Ok(resource::print_sentence::<models::SystemResource>(&Query::new())),
+                 single_item)
+    }
+
+    // Returns the name of the router.
+    fn identity(self) -> Self::Output<String> {
+        self.run(Ok(resource::print_sentence::<models::Identity>(&Query::new())),
+                 |response| Ok(single_item::<models::Identity>(response)?.name))
+    }
+
+    // Sets the name of the router.
+    fn set_identity(self, name: &str) -> Self::Output<()> {
+        self.run(Ok(client::identity_set_sentence(name)), done)
+    }
+
+    // Returns the readings of the sensors of the router, in the format of any
+    // RouterOS version.
+    fn health(self) -> Self::Output<models::Health> {
+        self.run(Ok(Query::new().sentence(&client::print_command("/system/health"))),
+                 |response| models::Health::from_sentence(&client::health_sentence(response)))
+    }
+
+    // Returns the date and time of the router.
+    fn clock(self) -> Self::Output<models::Clock> {
+        self.run(Ok(resource::print_sentence::<models::Clock>(&Query::new())), single_item)
+    }
+
+    // Returns the model and firmware of the router, on RouterBOARD hardware.
+    fn routerboard(self) -> Self::Output<models::Routerboard> {
+        self.run(Ok(resource::print_sentence::<models::Routerboard>(&Query::new())),
+                 single_item)
+    }
+}
+
+// Parses the replies of a command that returns nothing.
+fn done(_: &[Sentence]) -> Result<(), MikrotikError> {
+    Ok(())
+}
+
+// Parses the replies of a print command looking for a single item.
+fn first_item<T: Resource>(response: &[Sentence]) -> Result<Option<T>, MikrotikError> {
+    Ok(resource::parse_items(response)?.into_iter().next())
+}
+
+// Matches the item called 'name' in menus where names are unique.
+fn name_query(name: &str) -> Query {
+    Query::new().equal("name", name)
+}
+
+// Parses the replies of a print command in a menu holding a single item.
+fn single_item<T: Resource>(response: &[Sentence]) -> Result<T, MikrotikError> {
+    resource::single_item(resource::parse_items(response)?)
+}
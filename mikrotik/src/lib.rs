@@ -1,7 +1,44 @@
+// Lets the code generated by mikrotik-derive name this crate from inside it.
+extern crate self as mikrotik;
+
 pub mod errors;
+pub mod codec;
+pub mod encoding;
 pub mod client;
+pub mod executor;
+pub mod builder;
 pub mod utils;
 pub mod models;
+pub mod mux;
+pub mod listen;
+pub mod query;
+pub mod rate;
+pub mod resource;
+pub mod sentence;
+pub mod types;
+mod transport;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(feature = "async")]
+pub mod async_client;
+#[cfg(test)]
+mod test_utils;
 
 pub use errors::*;
 pub use client::Client;
+pub use executor::Executor;
+pub use encoding::Encoding;
+pub use builder::ClientBuilder;
+pub use mux::Multiplexer;
+pub use listen::{Listener, TrafficMonitor};
+pub use query::Query;
+pub use rate::{BurstTime, ProfileRateLimit, Rate, RateLimit};
+pub use resource::Resource;
+pub use mikrotik_derive::Resource;
+pub use sentence::{parse_attribute, Reply, Sentence};
+pub use types::{IpPrefix, ListAddress, MacAddress, RosBool, RosDate, RosDateTime, RosDuration,
+                RosTime};
+#[cfg(feature = "tls")]
+pub use tls::TlsOptions;
+#[cfg(feature = "async")]
+pub use async_client::{AsyncClient, AsyncListener, AsyncRequest, AsyncTrafficMonitor};
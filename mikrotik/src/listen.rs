@@ -0,0 +1,279 @@
+use crate::client::{self, Client};
+use crate::errors::MikrotikError;
+use crate::models::TrafficSample;
+use crate::sentence::{Reply, Sentence};
+
+// Event is what a sentence read while listening means for the listened command.
+pub(crate) enum Event {
+    Reply(Sentence),
+    Error(MikrotikError),
+    Done,
+    Ignore,
+}
+
+// Classifies a sentence received while listening to the command tagged with 'tag'.
+//
+// Replies to other tags and empty sentences are ignored.
+pub(crate) fn listen_event(words: &[String], tag: &str) -> Event {
+    if words.is_empty() {
+        return Event::Ignore;
+    }
+
+    let sentence = match Sentence::parse(words) {
+        Ok(s) => s,
+        Err(e) => return Event::Error(e),
+    };
+
+    if sentence.reply == Reply::Fatal {
+        return Event::Error(sentence.error().unwrap());
+    }
+
+    if sentence.tag.as_deref() != Some(tag) {
+        return Event::Ignore;
+    }
+
+    match sentence.reply {
+        Reply::Trap => Event::Error(sentence.error().unwrap()),
+        Reply::Done => Event::Done,
+        Reply::Empty => Event::Ignore,
+        _ => Event::Reply(sentence),
+    }
+}
+
+// Returns true if the error means the connection can no longer be used.
+pub(crate) fn is_terminal(err: &MikrotikError) -> bool {
+    !matches!(*err, MikrotikError::Trap { .. })
+}
+
+// Listener iterates over the replies of a command started with Client::listen.
+//
+// Each item is one '!re' sentence. A '!trap' for the command is yielded
+// as an error without ending the iteration, which ends once the router sends '!done'
+// or the connection fails.
+pub struct Listener<'a> {
+    client: &'a mut Client,
+    tag: String,
+    done: bool,
+}
+
+impl<'a> Listener<'a> {
+    pub(crate) fn new(client: &'a mut Client, tag: String) -> Listener<'a> {
+        Listener {
+            client,
+            tag,
+            done: false,
+        }
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    // Stops the command by sending '/cancel' with its tag and discards every reply
+    // still in flight for it.
+    pub fn cancel(mut self) -> Result<(), MikrotikError> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<(), MikrotikError> {
+        if self.done {
+            return Ok(());
+        }
+
+        let cancel_tag = self.client.next_tag();
+        let request = vec!["/cancel".to_string(), format!("=tag={}", self.tag)];
+        self.client.write_sentence(&client::tag_sentence(&request, &cancel_tag))?;
+
+        let mut cancel_error = None;
+        let mut confirmed = false;
+
+        while !self.done || !confirmed {
+            let sentence = match self.client.read_sentence() {
+                Ok(s) if s.is_empty() => continue,
+                Ok(s) => Sentence::parse(&s),
+                Err(e) => Err(e.into()),
+            };
+            let sentence = match sentence {
+                Ok(s) => s,
+                Err(e) => {
+                    self.done = true;
+                    return Err(e);
+                }
+            };
+
+            match sentence.tag.as_deref() {
+                Some(t) if t == self.tag => self.done = sentence.reply == Reply::Done,
+                Some(t) if t == cancel_tag => {
+                    match sentence.error() {
+                        Some(e) => cancel_error = Some(e),
+                        None => confirmed = sentence.reply == Reply::Done,
+                    }
+                }
+                _ => {
+                    if let Some(e @ MikrotikError::Fatal(_)) = sentence.error() {
+                        self.done = true;
+                        return Err(e);
+                    }
+                }
+            }
+
+            // The router refused to cancel, so the command will not be interrupted.
+            if confirmed && cancel_error.is_some() {
+                break;
+            }
+        }
+
+        match cancel_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Iterator for Listener<'_> {
+    type Item = Result<Sentence, MikrotikError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let sentence = match self.client.read_sentence() {
+                Ok(s) => s,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            };
+
+            match listen_event(&sentence, &self.tag) {
+                Event::Reply(sentence) => return Some(Ok(sentence)),
+                Event::Error(e) => {
+                    self.done = is_terminal(&e);
+                    return Some(Err(e));
+                }
+                Event::Done => self.done = true,
+                Event::Ignore => {}
+            }
+        }
+
+        None
+    }
+}
+
+impl Drop for Listener<'_> {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+// TrafficMonitor iterates over the samples of a command started with
+// Client::monitor_traffic, one per interface every second.
+//
+// Like Listener, dropping it cancels the command.
+pub struct TrafficMonitor<'a> {
+    listener: Listener<'a>,
+}
+
+impl<'a> TrafficMonitor<'a> {
+    pub(crate) fn new(listener: Listener<'a>) -> TrafficMonitor<'a> {
+        TrafficMonitor { listener }
+    }
+
+    // Stops monitoring. See Listener::cancel.
+    pub fn cancel(self) -> Result<(), MikrotikError> {
+        self.listener.cancel()
+    }
+}
+
+impl Iterator for TrafficMonitor<'_> {
+    type Item = Result<TrafficSample, MikrotikError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.listener.next().map(|r| r.and_then(|s| TrafficSample::from_sentence(&s)))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_utils::{mock_router, sentence_tag, words};
+
+    // Mock router that streams three replies to the first command, handles its
+    // cancellation and then answers one more command.
+    pub(crate) fn streaming_router() -> u16 {
+        mock_router(|conn| {
+            let request = conn.read();
+            let tag = format!(".tag={}", sentence_tag(&request).unwrap());
+            let sample = |n: &str| words(&["!re", &format!("=rx-bits-per-second={}", n), &tag]);
+            conn.reply(&[sample("100"), sample("200"), sample("300")]);
+
+            let cancel = conn.read();
+            assert_eq!(cancel[0], "/cancel");
+            assert_eq!(cancel[1], format!("={}", &tag[1..]));
+
+            let cancel_tag = format!(".tag={}", sentence_tag(&cancel).unwrap());
+            conn.reply(&[words(&["!trap", "=category=2", "=message=interrupted", &tag]),
+                         words(&["!done", &tag]),
+                         words(&["!done", &cancel_tag])]);
+
+            conn.expect(&["/system/identity/print"],
+                        &[words(&["!re", "=name=router"]), words(&["!done"])]);
+        })
+    }
+
+    #[test]
+    fn test_listen_and_cancel() {
+        let port = streaming_router();
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        {
+            let mut listener = client.listen(&words(&["/interface/monitor-traffic"])).unwrap();
+
+            let first = listener.next().unwrap().unwrap();
+            assert_eq!(first.get("rx-bits-per-second"), Some("100"));
+
+            let second = listener.next().unwrap().unwrap();
+            assert_eq!(second.get("rx-bits-per-second"), Some("200"));
+
+            listener.cancel().unwrap();
+        }
+
+        let response = client.execute(&words(&["/system/identity/print"])).unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+        assert_eq!(response[1].reply, Reply::Done);
+    }
+
+    #[test]
+    fn test_monitor_traffic() {
+        let port = streaming_router();
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        {
+            let mut monitor = client.monitor_traffic("ether1").unwrap();
+
+            let sample = monitor.next().unwrap().unwrap();
+            assert_eq!(sample.rx_bits_per_second.0, 100);
+            assert_eq!(sample.tx_bits_per_second.0, 0);
+
+            let sample = monitor.next().unwrap().unwrap();
+            assert_eq!(sample.rx_bits_per_second.0, 200);
+        }
+
+        let response = client.execute(&words(&["/system/identity/print"])).unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+    }
+
+    #[test]
+    fn test_dropping_listener_cancels_command() {
+        let port = streaming_router();
+        let mut client = Client::connect(("127.0.0.1", port)).unwrap();
+
+        let samples: Vec<_> = client.listen(&words(&["/interface/monitor-traffic"]))
+            .unwrap()
+            .take(1)
+            .collect();
+        assert_eq!(samples.len(), 1);
+
+        let response = client.execute(&words(&["/system/identity/print"])).unwrap();
+        assert_eq!(response[0].get("name"), Some("router"));
+        assert_eq!(response[1].reply, Reply::Done);
+    }
+}
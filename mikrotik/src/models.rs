@@ -1,31 +1,88 @@
-#[derive(Clone)]
+use crate::rate::{BurstTime, ProfileRateLimit, Rate, RateLimit};
+use crate::errors::MikrotikError;
+use crate::resource::parse_field;
+use crate::sentence::Sentence;
+use crate::types::{IpPrefix, ListAddress, MacAddress, RosDate, RosDateTime, RosDuration, RosTime};
+
+use std::net::IpAddr;
+
+use mikrotik_derive::Resource;
+
+// IPAddress is an entry of the firewall address list. 'address' is a single address,
+// a prefix, a range or a DNS name.
+//
+// Entries with a 'timeout' are dynamic and removed by the router once it expires.
+// The timeout read back from the router is the time left, not the one it was added
+// with.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/firewall/address-list")]
 pub struct IPAddress {
+    #[resource(id)]
     pub id: String,
-    pub address: String,
+    pub address: ListAddress,
     pub list: String,
+    pub timeout: Option<RosDuration>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub dynamic: bool,
+    #[resource(read_only)]
+    pub creation_time: Option<RosDateTime>,
+}
+
+impl Default for IPAddress {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl IPAddress {
     pub fn new() -> IPAddress {
         IPAddress {
             id: String::new(),
-            address: String::new(),
+            address: ListAddress::default(),
             list: String::new(),
+            timeout: None,
+            comment: None,
+            disabled: false,
+            dynamic: false,
+            creation_time: None,
         }
     }
 }
 
-#[derive(Clone)]
+// Client is a simple queue limiting the bandwidth of a customer.
+//
+// Limits and burst settings are 'upload/download' pairs, read from and written as
+// "10M/20M" and "8s/8s" in RouterOS notation.
+// 'queue' holds the queue types used for each direction, and 'priority' goes from 1,
+// the highest, to 8.
+#[derive(Clone, Resource)]
+#[resource(menu = "/queue/simple")]
 pub struct Client {
+    #[resource(id)]
     pub id: String,
     pub name: String,
     pub target: String,
-    pub max_limit: String,
-    pub burst_limit: String,
-    pub burst_threshold: String,
-    pub burst_time: String,
+    pub parent: Option<String>,
+    pub queue: Option<String>,
+    pub priority: Option<String>,
+    pub limit_at: Option<RateLimit>,
+    pub max_limit: RateLimit,
+    pub burst_limit: RateLimit,
+    pub burst_threshold: RateLimit,
+    pub burst_time: BurstTime,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub dynamic: bool,
 }
 
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Client {
     pub fn new() -> Client {
@@ -33,10 +90,1123 @@ impl Client {
             id: String::new(),
             name: String::new(),
             target: String::new(),
-            max_limit: String::new(),
-            burst_limit: String::new(),
-            burst_threshold: String::new(),
-            burst_time: String::new(),
+            parent: None,
+            queue: None,
+            priority: None,
+            limit_at: None,
+            max_limit: RateLimit::default(),
+            burst_limit: RateLimit::default(),
+            burst_threshold: RateLimit::default(),
+            burst_time: BurstTime::default(),
+            comment: None,
+            disabled: false,
+            dynamic: false,
+        }
+    }
+}
+
+// QueueTree is an entry of the queue tree, limiting the traffic of the packets marked
+// with 'packet_mark' under the interface or queue 'parent'.
+//
+// Unlike simple queues, its limits apply to a single direction. 'priority' goes from
+// 1, the highest, to 8.
+#[derive(Clone, Resource)]
+#[resource(menu = "/queue/tree")]
+pub struct QueueTree {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub parent: String,
+    pub packet_mark: Option<String>,
+    pub queue: Option<String>,
+    pub priority: Option<u8>,
+    pub limit_at: Option<Rate>,
+    pub max_limit: Option<Rate>,
+    pub burst_limit: Option<Rate>,
+    pub burst_threshold: Option<Rate>,
+    pub burst_time: Option<RosDuration>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub invalid: bool,
+}
+
+impl Default for QueueTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueueTree {
+    pub fn new() -> QueueTree {
+        QueueTree {
+            id: String::new(),
+            name: String::new(),
+            parent: String::new(),
+            packet_mark: None,
+            queue: None,
+            priority: None,
+            limit_at: None,
+            max_limit: None,
+            burst_limit: None,
+            burst_threshold: None,
+            burst_time: None,
+            comment: None,
+            disabled: false,
+            invalid: false,
+        }
+    }
+}
+
+// QueueType is a queue type, such as the PCQ ones used to share a limit equally among
+// the customers behind a queue.
+//
+// 'kind' is the queuing discipline, such as "pcq" or "sfq". The 'pcq_' settings are
+// only sent by the router for PCQ types: 'pcq_rate' limits each sub-stream, and
+// 'pcq_classifier' lists the packet fields sub-streams are told apart by, such as
+// "dst-address" or "src-address,src-port". The limits are in packets.
+#[derive(Clone, Resource)]
+#[resource(menu = "/queue/type")]
+pub struct QueueType {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub pcq_rate: Option<Rate>,
+    pub pcq_classifier: Option<String>,
+    pub pcq_limit: Option<u32>,
+    pub pcq_total_limit: Option<u32>,
+    #[resource(read_only)]
+    pub default: bool,
+}
+
+impl Default for QueueType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueueType {
+    pub fn new() -> QueueType {
+        QueueType {
+            id: String::new(),
+            name: String::new(),
+            kind: String::new(),
+            pcq_rate: None,
+            pcq_classifier: None,
+            pcq_limit: None,
+            pcq_total_limit: None,
+            default: false,
+        }
+    }
+
+    // Returns a PCQ type giving every sub-stream told apart by 'classifier' up to
+    // 'rate', or an equal share of the parent queue when 'rate' is 0.
+    pub fn pcq(name: &str, classifier: &str, rate: Rate) -> QueueType {
+        QueueType {
+            name: name.to_string(),
+            kind: String::from("pcq"),
+            pcq_rate: Some(rate),
+            pcq_classifier: Some(classifier.to_string()),
+            ..QueueType::new()
+        }
+    }
+}
+
+// Interface is an entry of '/interface', of any type such as "ether", "vlan" or
+// "pppoe-in".
+//
+// 'mtu' is kept as the router writes it, since some types accept "auto", while
+// 'actual_mtu' is the one in use. The traffic counters are read only and count since
+// the router booted or they were reset.
+#[derive(Clone, Resource)]
+#[resource(menu = "/interface")]
+pub struct Interface {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    #[resource(read_only)]
+    pub r#type: String,
+    pub mtu: Option<String>,
+    #[resource(read_only)]
+    pub actual_mtu: Option<u32>,
+    #[resource(read_only)]
+    pub mac_address: Option<MacAddress>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub running: bool,
+    #[resource(read_only)]
+    pub rx_byte: u64,
+    #[resource(read_only)]
+    pub tx_byte: u64,
+    #[resource(read_only)]
+    pub rx_packet: u64,
+    #[resource(read_only)]
+    pub tx_packet: u64,
+}
+
+impl Default for Interface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interface {
+    pub fn new() -> Interface {
+        Interface {
+            id: String::new(),
+            name: String::new(),
+            r#type: String::new(),
+            mtu: None,
+            actual_mtu: None,
+            mac_address: None,
+            comment: None,
+            disabled: false,
+            running: false,
+            rx_byte: 0,
+            tx_byte: 0,
+            rx_packet: 0,
+            tx_packet: 0,
+        }
+    }
+}
+
+// TrafficSample is the traffic of an interface over the last second, as sent by
+// '/interface/monitor-traffic'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrafficSample {
+    pub name: String,
+    pub rx_bits_per_second: Rate,
+    pub tx_bits_per_second: Rate,
+    pub rx_packets_per_second: u64,
+    pub tx_packets_per_second: u64,
+}
+
+impl Default for TrafficSample {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrafficSample {
+    pub fn new() -> TrafficSample {
+        TrafficSample {
+            name: String::new(),
+            rx_bits_per_second: Rate::default(),
+            tx_bits_per_second: Rate::default(),
+            rx_packets_per_second: 0,
+            tx_packets_per_second: 0,
+        }
+    }
+
+    pub fn from_sentence(sentence: &Sentence) -> Result<TrafficSample, MikrotikError> {
+        Ok(TrafficSample {
+            name: parse_field(sentence, "name")?,
+            rx_bits_per_second: parse_field(sentence, "rx-bits-per-second")?,
+            tx_bits_per_second: parse_field(sentence, "tx-bits-per-second")?,
+            rx_packets_per_second: parse_field(sentence, "rx-packets-per-second")?,
+            tx_packets_per_second: parse_field(sentence, "tx-packets-per-second")?,
+        })
+    }
+}
+
+// DhcpLease is a lease of a DHCP server.
+//
+// Leases handed out by the server are dynamic until made static, which pins the
+// address to the client. 'server' is the name of the DHCP server the lease belongs
+// to, or "all". 'address' is kept as written, since it can name an IP pool to lease
+// from instead of an address. 'last_seen' is kept as written too, since it is "never"
+// for leases the client has not used yet.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/dhcp-server/lease")]
+pub struct DhcpLease {
+    #[resource(id)]
+    pub id: String,
+    pub address: String,
+    pub mac_address: MacAddress,
+    pub client_id: Option<String>,
+    pub server: Option<String>,
+    pub lease_time: Option<RosDuration>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub active_address: Option<IpAddr>,
+    #[resource(read_only)]
+    pub host_name: Option<String>,
+    #[resource(read_only)]
+    pub status: Option<String>,
+    #[resource(read_only)]
+    pub expires_after: Option<RosDuration>,
+    #[resource(read_only)]
+    pub last_seen: Option<String>,
+    #[resource(read_only)]
+    pub dynamic: bool,
+    #[resource(read_only)]
+    pub blocked: bool,
+}
+
+impl Default for DhcpLease {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DhcpLease {
+    pub fn new() -> DhcpLease {
+        DhcpLease {
+            id: String::new(),
+            address: String::new(),
+            mac_address: MacAddress::default(),
+            client_id: None,
+            server: None,
+            lease_time: None,
+            comment: None,
+            disabled: false,
+            active_address: None,
+            host_name: None,
+            status: None,
+            expires_after: None,
+            last_seen: None,
+            dynamic: false,
+            blocked: false,
+        }
+    }
+}
+
+// DhcpServer is a DHCP server running on 'interface' and leasing addresses from the
+// IP pool 'address_pool'.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/dhcp-server")]
+pub struct DhcpServer {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub interface: String,
+    pub address_pool: Option<String>,
+    pub lease_time: Option<RosDuration>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub invalid: bool,
+    #[resource(read_only)]
+    pub dynamic: bool,
+}
+
+impl Default for DhcpServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DhcpServer {
+    pub fn new() -> DhcpServer {
+        DhcpServer {
+            id: String::new(),
+            name: String::new(),
+            interface: String::new(),
+            address_pool: None,
+            lease_time: None,
+            disabled: false,
+            invalid: false,
+            dynamic: false,
+        }
+    }
+}
+
+// DhcpNetwork holds the settings given to the clients of DHCP servers leasing
+// addresses in the prefix 'address'. 'gateway' and 'dns_server' are comma separated
+// lists.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/dhcp-server/network")]
+pub struct DhcpNetwork {
+    #[resource(id)]
+    pub id: String,
+    pub address: IpPrefix,
+    pub gateway: Option<String>,
+    pub netmask: Option<u8>,
+    pub dns_server: Option<String>,
+    pub domain: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Default for DhcpNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DhcpNetwork {
+    pub fn new() -> DhcpNetwork {
+        DhcpNetwork {
+            id: String::new(),
+            address: IpPrefix::default(),
+            gateway: None,
+            netmask: None,
+            dns_server: None,
+            domain: None,
+            comment: None,
+        }
+    }
+}
+
+// PppSecret is the account of a PPP client, such as a PPPoE customer.
+//
+// 'service' restricts the account to one kind of tunnel, such as "pppoe", or allows
+// "any". Addresses left unset are taken from 'profile'. They are kept as written,
+// since each can name an IP pool instead of an address.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ppp/secret")]
+pub struct PppSecret {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub password: String,
+    pub service: Option<String>,
+    pub profile: Option<String>,
+    pub local_address: Option<String>,
+    pub remote_address: Option<String>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub last_logged_out: Option<String>,
+}
+
+impl Default for PppSecret {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PppSecret {
+    pub fn new() -> PppSecret {
+        PppSecret {
+            id: String::new(),
+            name: String::new(),
+            password: String::new(),
+            service: None,
+            profile: None,
+            local_address: None,
+            remote_address: None,
+            comment: None,
+            disabled: false,
+            last_logged_out: None,
+        }
+    }
+}
+
+// PppActive is a connected PPP session. Removing it disconnects the client.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ppp/active")]
+pub struct PppActive {
+    #[resource(id)]
+    pub id: String,
+    #[resource(read_only)]
+    pub name: String,
+    #[resource(read_only)]
+    pub service: String,
+    #[resource(read_only)]
+    pub caller_id: Option<String>,
+    #[resource(read_only)]
+    pub address: Option<IpAddr>,
+    #[resource(read_only)]
+    pub uptime: Option<RosDuration>,
+    #[resource(read_only)]
+    pub encoding: Option<String>,
+    #[resource(read_only)]
+    pub session_id: Option<String>,
+    #[resource(read_only)]
+    pub radius: bool,
+}
+
+impl Default for PppActive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PppActive {
+    pub fn new() -> PppActive {
+        PppActive {
+            id: String::new(),
+            name: String::new(),
+            service: String::new(),
+            caller_id: None,
+            address: None,
+            uptime: None,
+            encoding: None,
+            session_id: None,
+            radius: false,
+        }
+    }
+}
+
+// PppProfile holds the settings shared by the PPP secrets using it. Like those of
+// PppSecret, its addresses can name an IP pool. 'only_one' is "yes", "no" or
+// "default".
+#[derive(Clone, Resource)]
+#[resource(menu = "/ppp/profile")]
+pub struct PppProfile {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub local_address: Option<String>,
+    pub remote_address: Option<String>,
+    pub rate_limit: Option<ProfileRateLimit>,
+    pub only_one: Option<String>,
+    pub comment: Option<String>,
+    #[resource(read_only)]
+    pub default: bool,
+}
+
+impl Default for PppProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PppProfile {
+    pub fn new() -> PppProfile {
+        PppProfile {
+            id: String::new(),
+            name: String::new(),
+            local_address: None,
+            remote_address: None,
+            rate_limit: None,
+            only_one: None,
+            comment: None,
+            default: false,
+        }
+    }
+}
+
+// HotspotUser is an account of the hotspot.
+//
+// The limits end the sessions of the user once reached: 'limit_uptime' is the total
+// time online, and the byte limits the total traffic, counted by the router in
+// 'uptime', 'bytes_in' and 'bytes_out'. A user can be bound to one 'address' or
+// 'mac_address'.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/hotspot/user")]
+pub struct HotspotUser {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub password: String,
+    pub server: Option<String>,
+    pub profile: Option<String>,
+    pub address: Option<IpAddr>,
+    pub mac_address: Option<MacAddress>,
+    pub limit_uptime: Option<RosDuration>,
+    pub limit_bytes_in: Option<u64>,
+    pub limit_bytes_out: Option<u64>,
+    pub limit_bytes_total: Option<u64>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub uptime: Option<RosDuration>,
+    #[resource(read_only)]
+    pub bytes_in: u64,
+    #[resource(read_only)]
+    pub bytes_out: u64,
+    #[resource(read_only)]
+    pub dynamic: bool,
+}
+
+impl Default for HotspotUser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotspotUser {
+    pub fn new() -> HotspotUser {
+        HotspotUser {
+            id: String::new(),
+            name: String::new(),
+            password: String::new(),
+            server: None,
+            profile: None,
+            address: None,
+            mac_address: None,
+            limit_uptime: None,
+            limit_bytes_in: None,
+            limit_bytes_out: None,
+            limit_bytes_total: None,
+            comment: None,
+            disabled: false,
+            uptime: None,
+            bytes_in: 0,
+            bytes_out: 0,
+            dynamic: false,
+        }
+    }
+}
+
+// HotspotActive is a logged in hotspot session. Removing it logs the user out.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/hotspot/active")]
+pub struct HotspotActive {
+    #[resource(id)]
+    pub id: String,
+    #[resource(read_only)]
+    pub server: Option<String>,
+    #[resource(read_only)]
+    pub user: String,
+    #[resource(read_only)]
+    pub address: Option<IpAddr>,
+    #[resource(read_only)]
+    pub mac_address: Option<MacAddress>,
+    #[resource(read_only)]
+    pub login_by: Option<String>,
+    #[resource(read_only)]
+    pub uptime: Option<RosDuration>,
+    #[resource(read_only)]
+    pub session_time_left: Option<RosDuration>,
+    #[resource(read_only)]
+    pub idle_time: Option<RosDuration>,
+    #[resource(read_only)]
+    pub bytes_in: u64,
+    #[resource(read_only)]
+    pub bytes_out: u64,
+}
+
+impl Default for HotspotActive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotspotActive {
+    pub fn new() -> HotspotActive {
+        HotspotActive {
+            id: String::new(),
+            server: None,
+            user: String::new(),
+            address: None,
+            mac_address: None,
+            login_by: None,
+            uptime: None,
+            session_time_left: None,
+            idle_time: None,
+            bytes_in: 0,
+            bytes_out: 0,
+        }
+    }
+}
+
+// HotspotUserProfile holds the settings shared by the hotspot users using it.
+//
+// 'shared_users' is the number of sessions a user can have at once, or "unlimited".
+// 'idle_timeout' is kept as written, since it is "none" when disabled.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/hotspot/user/profile")]
+pub struct HotspotUserProfile {
+    #[resource(id)]
+    pub id: String,
+    pub name: String,
+    pub shared_users: Option<String>,
+    pub rate_limit: Option<ProfileRateLimit>,
+    pub session_timeout: Option<RosDuration>,
+    pub keepalive_timeout: Option<RosDuration>,
+    pub idle_timeout: Option<String>,
+    pub address_pool: Option<String>,
+    #[resource(read_only)]
+    pub default: bool,
+}
+
+impl Default for HotspotUserProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotspotUserProfile {
+    pub fn new() -> HotspotUserProfile {
+        HotspotUserProfile {
+            id: String::new(),
+            name: String::new(),
+            shared_users: None,
+            rate_limit: None,
+            session_timeout: None,
+            keepalive_timeout: None,
+            idle_timeout: None,
+            address_pool: None,
+            default: false,
+        }
+    }
+}
+
+// Defines a firewall rule with the fields every firewall menu shares: its chain, action
+// and conditions, the fields given for the menu, then its comment, state and
+// counters. The fields given must be optional, as new() leaves them unset.
+macro_rules! firewall_rule {
+    ($(#[$attr:meta])*
+     pub struct $name:ident {
+         $(pub $field:ident: Option<$ty:ty>,)*
+     }) => {
+        #[derive(Clone, Resource)]
+        $(#[$attr])*
+        pub struct $name {
+            #[resource(id)]
+            pub id: String,
+            pub chain: String,
+            pub action: Option<String>,
+            pub protocol: Option<String>,
+            pub src_address: Option<String>,
+            pub dst_address: Option<String>,
+            pub src_address_list: Option<String>,
+            pub dst_address_list: Option<String>,
+            pub src_port: Option<String>,
+            pub dst_port: Option<String>,
+            pub in_interface: Option<String>,
+            pub out_interface: Option<String>,
+            pub connection_state: Option<String>,
+            $(pub $field: Option<$ty>,)*
+            pub comment: Option<String>,
+            pub disabled: bool,
+            #[resource(read_only)]
+            pub bytes: u64,
+            #[resource(read_only)]
+            pub packets: u64,
+            #[resource(read_only)]
+            pub dynamic: bool,
+            #[resource(read_only)]
+            pub invalid: bool,
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            pub fn new() -> $name {
+                $name {
+                    id: String::new(),
+                    chain: String::new(),
+                    action: None,
+                    protocol: None,
+                    src_address: None,
+                    dst_address: None,
+                    src_address_list: None,
+                    dst_address_list: None,
+                    src_port: None,
+                    dst_port: None,
+                    in_interface: None,
+                    out_interface: None,
+                    connection_state: None,
+                    $($field: None,)*
+                    comment: None,
+                    disabled: false,
+                    bytes: 0,
+                    packets: 0,
+                    dynamic: false,
+                    invalid: false,
+                }
+            }
+        }
+    }
+}
+
+// FilterRule is a rule of the firewall filter, which accepts, drops or rejects the
+// packets of 'chain' ("input", "forward" or "output") matching all of its conditions.
+//
+// Rules are matched in order, so new ones are added at the end of the chain unless
+// moved. Conditions take the same values as in the terminal, such as "80,443" for a
+// port or "!10.0.0.0/8" for an address not in a prefix. 'bytes' and 'packets' count
+// the traffic matched.
+firewall_rule! {
+    #[resource(menu = "/ip/firewall/filter")]
+    pub struct FilterRule {
+        pub reject_with: Option<String>,
+        pub log: Option<bool>,
+        pub log_prefix: Option<String>,
+    }
+}
+
+// NatRule is a rule of the firewall NAT, rewriting the addresses of the packets of
+// 'chain' ("srcnat" or "dstnat"). A port forward is a "dst-nat" action with
+// 'to_addresses' and 'to_ports' set. See FilterRule for the conditions.
+firewall_rule! {
+    #[resource(menu = "/ip/firewall/nat")]
+    pub struct NatRule {
+        pub to_addresses: Option<String>,
+        pub to_ports: Option<String>,
+    }
+}
+
+// MangleRule is a rule of the firewall mangle, marking the packets or connections it
+// matches for queues and routing. 'passthrough' makes the next rules be matched after
+// it. See FilterRule for the conditions.
+firewall_rule! {
+    #[resource(menu = "/ip/firewall/mangle")]
+    pub struct MangleRule {
+        pub connection_mark: Option<String>,
+        pub packet_mark: Option<String>,
+        pub new_connection_mark: Option<String>,
+        pub new_packet_mark: Option<String>,
+        pub new_routing_mark: Option<String>,
+        pub passthrough: Option<bool>,
+    }
+}
+
+// InterfaceAddress is an IPv4 address of '/ip/address', assigned to 'interface'.
+//
+// 'address' holds the prefix of the network as well, such as "192.168.88.1/24", and
+// 'network' is computed by the router from it when not set.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/address")]
+pub struct InterfaceAddress {
+    #[resource(id)]
+    pub id: String,
+    pub address: IpPrefix,
+    pub network: Option<IpAddr>,
+    pub interface: String,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub actual_interface: Option<String>,
+    #[resource(read_only)]
+    pub dynamic: bool,
+    #[resource(read_only)]
+    pub invalid: bool,
+}
+
+impl Default for InterfaceAddress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InterfaceAddress {
+    pub fn new() -> InterfaceAddress {
+        InterfaceAddress {
+            id: String::new(),
+            address: IpPrefix::default(),
+            network: None,
+            interface: String::new(),
+            comment: None,
+            disabled: false,
+            actual_interface: None,
+            dynamic: false,
+            invalid: false,
+        }
+    }
+}
+
+// Route is an entry of the routing table '/ip/route'.
+//
+// 'gateway' is kept as written, since it can be an address, an interface or both, as
+// in "10.0.0.1%ether1". 'routing_table' is the table of RouterOS 7, "main" unless
+// set. The flags are read only: 'active' routes are the ones used to forward packets,
+// and 'connect' ones lead to the networks of the router's own addresses.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/route")]
+pub struct Route {
+    #[resource(id)]
+    pub id: String,
+    pub dst_address: IpPrefix,
+    pub gateway: Option<String>,
+    pub distance: Option<u8>,
+    pub routing_table: Option<String>,
+    pub pref_src: Option<IpAddr>,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub immediate_gw: Option<String>,
+    #[resource(read_only)]
+    pub active: bool,
+    #[resource(read_only)]
+    pub dynamic: bool,
+    #[resource(read_only)]
+    pub r#static: bool,
+    #[resource(read_only)]
+    pub connect: bool,
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Route {
+    pub fn new() -> Route {
+        Route {
+            id: String::new(),
+            dst_address: IpPrefix::default(),
+            gateway: None,
+            distance: None,
+            routing_table: None,
+            pref_src: None,
+            comment: None,
+            disabled: false,
+            immediate_gw: None,
+            active: false,
+            dynamic: false,
+            r#static: false,
+            connect: false,
+        }
+    }
+}
+
+// ArpEntry maps an IPv4 address to the MAC address of a host on 'interface'. Entries
+// learned by the router are dynamic, and 'complete' once the host answered.
+#[derive(Clone, Resource)]
+#[resource(menu = "/ip/arp")]
+pub struct ArpEntry {
+    #[resource(id)]
+    pub id: String,
+    pub address: Option<IpAddr>,
+    pub mac_address: Option<MacAddress>,
+    pub interface: String,
+    pub comment: Option<String>,
+    pub disabled: bool,
+    #[resource(read_only)]
+    pub dynamic: bool,
+    #[resource(read_only)]
+    pub complete: bool,
+    #[resource(read_only)]
+    pub invalid: bool,
+}
+
+impl Default for ArpEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArpEntry {
+    pub fn new() -> ArpEntry {
+        ArpEntry {
+            id: String::new(),
+            address: None,
+            mac_address: None,
+            interface: String::new(),
+            comment: None,
+            disabled: false,
+            dynamic: false,
+            complete: false,
+            invalid: false,
+        }
+    }
+}
+
+// SystemResource is the state of the router from '/system/resource'.
+//
+// Memory and disk sizes are in bytes, 'cpu_frequency' in MHz and 'cpu_load' in
+// percent. 'version' includes the release channel, as in "7.12.1 (stable)".
+#[derive(Clone, Resource)]
+#[resource(menu = "/system/resource")]
+pub struct SystemResource {
+    #[resource(read_only)]
+    pub uptime: RosDuration,
+    #[resource(read_only)]
+    pub version: String,
+    #[resource(read_only)]
+    pub build_time: Option<RosDateTime>,
+    #[resource(read_only)]
+    pub board_name: String,
+    #[resource(read_only)]
+    pub platform: String,
+    #[resource(read_only)]
+    pub architecture_name: String,
+    #[resource(read_only)]
+    pub cpu: String,
+    #[resource(read_only)]
+    pub cpu_count: u32,
+    #[resource(read_only)]
+    pub cpu_frequency: Option<u32>,
+    #[resource(read_only)]
+    pub cpu_load: u8,
+    #[resource(read_only)]
+    pub free_memory: u64,
+    #[resource(read_only)]
+    pub total_memory: u64,
+    #[resource(read_only)]
+    pub free_hdd_space: u64,
+    #[resource(read_only)]
+    pub total_hdd_space: u64,
+}
+
+impl Default for SystemResource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemResource {
+    pub fn new() -> SystemResource {
+        SystemResource {
+            uptime: RosDuration::default(),
+            version: String::new(),
+            build_time: None,
+            board_name: String::new(),
+            platform: String::new(),
+            architecture_name: String::new(),
+            cpu: String::new(),
+            cpu_count: 0,
+            cpu_frequency: None,
+            cpu_load: 0,
+            free_memory: 0,
+            total_memory: 0,
+            free_hdd_space: 0,
+            total_hdd_space: 0,
+        }
+    }
+}
+
+// Identity is the name of the router.
+#[derive(Clone, Resource)]
+#[resource(menu = "/system/identity")]
+pub struct Identity {
+    pub name: String,
+}
+
+impl Default for Identity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Identity {
+    pub fn new() -> Identity {
+        Identity {
+            name: String::new(),
+        }
+    }
+}
+
+// Health holds the readings of the sensors of the router, in volts, amperes, degrees
+// Celsius, watts and RPM. Routers only report the sensors they have, so every reading
+// is optional.
+#[derive(Clone, Debug, PartialEq, Resource)]
+#[resource(menu = "/system/health")]
+pub struct Health {
+    #[resource(read_only)]
+    pub voltage: Option<f64>,
+    #[resource(read_only)]
+    pub current: Option<f64>,
+    #[resource(read_only)]
+    pub temperature: Option<f64>,
+    #[resource(read_only)]
+    pub cpu_temperature: Option<f64>,
+    #[resource(read_only)]
+    pub board_temperature1: Option<f64>,
+    #[resource(read_only)]
+    pub power_consumption: Option<f64>,
+    #[resource(read_only)]
+    pub fan1_speed: Option<u32>,
+    #[resource(read_only)]
+    pub fan2_speed: Option<u32>,
+    #[resource(read_only)]
+    pub psu1_state: Option<String>,
+    #[resource(read_only)]
+    pub psu2_state: Option<String>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Health {
+    pub fn new() -> Health {
+        Health {
+            voltage: None,
+            current: None,
+            temperature: None,
+            cpu_temperature: None,
+            board_temperature1: None,
+            power_consumption: None,
+            fan1_speed: None,
+            fan2_speed: None,
+            psu1_state: None,
+            psu2_state: None,
+        }
+    }
+}
+
+// Clock is the date and time of the router, in the time zone 'time_zone_name'.
+// 'gmt_offset' is written as "+02:00".
+#[derive(Clone, Resource)]
+#[resource(menu = "/system/clock")]
+pub struct Clock {
+    #[resource(read_only)]
+    pub date: RosDate,
+    #[resource(read_only)]
+    pub time: RosTime,
+    #[resource(read_only)]
+    pub time_zone_name: Option<String>,
+    #[resource(read_only)]
+    pub gmt_offset: Option<String>,
+    #[resource(read_only)]
+    pub dst_active: bool,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            date: RosDate::default(),
+            time: RosTime::default(),
+            time_zone_name: None,
+            gmt_offset: None,
+            dst_active: false,
+        }
+    }
+}
+
+impl Clock {
+    pub fn date_time(&self) -> RosDateTime {
+        RosDateTime { date: self.date, time: self.time }
+    }
+}
+
+// Routerboard describes the hardware and firmware of a RouterBOARD. 'routerboard' is
+// false on other hardware, such as a CHR, which leave the rest unset.
+#[derive(Clone, Resource)]
+#[resource(menu = "/system/routerboard")]
+pub struct Routerboard {
+    #[resource(read_only)]
+    pub routerboard: bool,
+    #[resource(read_only)]
+    pub model: Option<String>,
+    #[resource(read_only)]
+    pub serial_number: Option<String>,
+    #[resource(read_only)]
+    pub firmware_type: Option<String>,
+    #[resource(read_only)]
+    pub factory_firmware: Option<String>,
+    #[resource(read_only)]
+    pub current_firmware: Option<String>,
+    #[resource(read_only)]
+    pub upgrade_firmware: Option<String>,
+}
+
+impl Default for Routerboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Routerboard {
+    pub fn new() -> Routerboard {
+        Routerboard {
+            routerboard: false,
+            model: None,
+            serial_number: None,
+            firmware_type: None,
+            factory_firmware: None,
+            current_firmware: None,
+            upgrade_firmware: None,
         }
     }
 }
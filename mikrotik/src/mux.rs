@@ -0,0 +1,318 @@
+use crate::client::{self, Client};
+use crate::codec;
+use crate::encoding::Encoding;
+use crate::errors;
+use crate::executor::{Executor, Parser};
+use crate::sentence::{Reply, Sentence};
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Response = Result<Sentence, errors::MikrotikError>;
+type Pending = Arc<Mutex<HashMap<String, Sender<Response>>>>;
+
+// Multiplexer lets several callers share a single logged-in connection.
+//
+// Every request is sent with a unique '.tag=' attribute. A background thread reads
+// all the sentences coming from the router and routes each '!re', '!trap' and '!done'
+// to the caller that sent the request with the same tag, so many commands can be in
+// flight on the same socket at once.
+//
+// API documentation:
+//     http://wiki.mikrotik.com/wiki/Manual:API#Tags
+//
+pub struct Multiplexer {
+    writer: Mutex<TcpStream>,
+    pending: Pending,
+    closed: Arc<AtomicBool>,
+    next_tag: AtomicUsize,
+    encoding: Encoding,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl Multiplexer {
+    // Takes over the connection of an already logged-in client.
+    pub fn new(client: Client) -> Result<Multiplexer, io::Error> {
+        let (stream, writer, decoder, encoding) = client.split()?;
+        let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let reader = {
+            let pending = pending.clone();
+            let closed = closed.clone();
+
+            thread::spawn(move || read_loop(stream, decoder, encoding, &pending, &closed))
+        };
+
+        Ok(Multiplexer {
+            writer: Mutex::new(writer),
+            pending,
+            closed,
+            next_tag: AtomicUsize::new(0),
+            encoding,
+            reader: Some(reader),
+        })
+    }
+
+    // Tags 'sentence', registers a mailbox for its replies and sends it.
+    fn send(&self, sentence: &[String]) -> Result<Receiver<Response>, io::Error> {
+        let tag = self.next_tag.fetch_add(1, Ordering::SeqCst).to_string();
+        let (tx, rx) = mpsc::channel();
+
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if self.closed.load(Ordering::SeqCst) {
+                let err = io::Error::new(io::ErrorKind::NotConnected,
+                                         "error writing: connection is closed");
+                return Err(err);
+            }
+
+            pending.insert(tag.clone(), tx);
+        }
+
+        let words = self.encoding.encode_words(&client::tag_sentence(sentence, &tag));
+
+        let mut buffer: Vec<u8> = Vec::new();
+        codec::encode_sentence(&words, &mut buffer)?;
+
+        if let Err(e) = self.writer.lock().unwrap().write_all(&buffer) {
+            self.pending.lock().unwrap().remove(&tag);
+            return Err(e);
+        }
+
+        Ok(rx)
+    }
+
+    // Sends 'sentence' and waits for all its replies. See Client::execute.
+    pub fn execute(&self, sentence: &[String]) -> Result<Vec<Sentence>, errors::MikrotikError> {
+        if sentence.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let rx = self.send(sentence)?;
+        let mut response: Vec<Sentence> = Vec::new();
+
+        loop {
+            let sentence = match rx.recv() {
+                Ok(reply) => reply?,
+                Err(_) => return Err(closed_error().into()),
+            };
+
+            if let Some(e) = sentence.error() {
+                return Err(e);
+            }
+
+            let is_done = sentence.reply == Reply::Done;
+            response.push(sentence);
+
+            if is_done {
+                return Ok(response);
+            }
+        }
+    }
+
+}
+
+impl Executor for &Multiplexer {
+    type Output<R> = Result<R, errors::MikrotikError>;
+
+    fn run<R>(self,
+              sentence: Result<Vec<String>, errors::MikrotikError>,
+              parse: Parser<R>)
+              -> Result<R, errors::MikrotikError> {
+        parse(&self.execute(&sentence?)?)
+    }
+}
+
+impl Drop for Multiplexer {
+    fn drop(&mut self) {
+        if let Ok(writer) = self.writer.lock() {
+            let _ = writer.shutdown(Shutdown::Both);
+        }
+
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+fn closed_error() -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionReset,
+                   "error reading: connection closed by remote end")
+}
+
+// Routes every sentence read from the router to the mailbox of the request with the
+// same tag until the connection is closed.
+fn read_loop(mut stream: TcpStream,
+             mut decoder: codec::Decoder,
+             encoding: Encoding,
+             pending: &Pending,
+             closed: &AtomicBool) {
+    let err = loop {
+        let words = match client::read_words(&mut stream, &mut decoder) {
+            Ok(w) => encoding.decode_words(&w),
+            Err(e) => break errors::MikrotikError::IoError(e),
+        };
+
+        if words.is_empty() {
+            continue;
+        }
+
+        let sentence = match Sentence::parse(&words) {
+            Ok(s) => s,
+            Err(e) => break e,
+        };
+
+        // A fatal reply is not tagged and the router closes the connection right
+        // after it, so every pending request gets the error.
+        if sentence.reply == Reply::Fatal {
+            break sentence.error().unwrap();
+        }
+
+        let tag = match sentence.tag {
+            Some(ref tag) => tag.clone(),
+            None => continue,
+        };
+
+        let mut pending = pending.lock().unwrap();
+        let tx = if sentence.reply == Reply::Done {
+            pending.remove(&tag)
+        } else {
+            pending.get(&tag).cloned()
+        };
+
+        if let Some(tx) = tx {
+            let _ = tx.send(Ok(sentence));
+        }
+    };
+
+    let mut pending = pending.lock().unwrap();
+    closed.store(true, Ordering::SeqCst);
+
+    for (_, tx) in pending.drain() {
+        let reply = match err {
+            errors::MikrotikError::IoError(ref e) => {
+                errors::MikrotikError::IoError(io::Error::new(e.kind(), e.to_string()))
+            }
+            errors::MikrotikError::Fatal(ref msg) => errors::MikrotikError::Fatal(msg.clone()),
+            errors::MikrotikError::UnexpectedReply(ref msg) => {
+                errors::MikrotikError::UnexpectedReply(msg.clone())
+            }
+            _ => errors::MikrotikError::IoError(closed_error()),
+        };
+
+        let _ = tx.send(Err(reply));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models;
+    use crate::query::Query;
+    use crate::test_utils::{mock_router, sentence_tag, words};
+
+    use std::io::Read;
+
+    // Mock router that waits for the '/first' and '/second' requests and answers
+    // them in reverse order, interleaving the replies.
+    fn interleaving_router() -> u16 {
+        mock_router(|conn| {
+            let mut tags = HashMap::new();
+
+            while tags.len() < 2 {
+                let sentence = conn.read();
+                let tag = sentence_tag(&sentence).unwrap().to_string();
+                tags.insert(sentence[0].clone(), tag);
+            }
+
+            let reply = |w: &[&str], command: &str| {
+                let mut s = words(w);
+                s.push(format!(".tag={}", tags[command]));
+                s
+            };
+
+            conn.reply(&[reply(&["!re", "=name=second"], "/second"),
+                         reply(&["!re", "=name=first"], "/first"),
+                         reply(&["!trap", "=category=1", "=message=bad value"], "/second"),
+                         reply(&["!done"], "/second"),
+                         reply(&["!done"], "/first")]);
+
+            let mut buffer = [0; 16];
+            let _ = conn.stream.read(&mut buffer);
+        })
+    }
+
+    #[test]
+    fn test_concurrent_requests_are_routed_by_tag() {
+        let port = interleaving_router();
+        let client = Client::connect(("127.0.0.1", port)).unwrap();
+        let mux = Multiplexer::new(client).unwrap();
+
+        let (first, second) = thread::scope(|s| {
+            let first = s.spawn(|| mux.execute(&words(&["/first"])));
+            let second = s.spawn(|| mux.execute(&words(&["/second"])));
+
+            (first.join().unwrap(), second.join().unwrap())
+        });
+
+        let first = first.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].get("name"), Some("first"));
+        assert_eq!(first[1].reply, Reply::Done);
+
+        match second {
+            Err(errors::MikrotikError::Trap { category, msg }) => {
+                assert_eq!(category, 1);
+                assert_eq!(msg, "bad value");
+            }
+            _ => panic!("expected a trap error"),
+        }
+    }
+
+    #[test]
+    fn test_pending_requests_fail_when_connection_closes() {
+        let port = mock_router(|conn| {
+            conn.read();
+            conn.reply(&[words(&["!fatal", "session terminated"])]);
+        });
+
+        let client = Client::connect(("127.0.0.1", port)).unwrap();
+        let mux = Multiplexer::new(client).unwrap();
+
+        match mux.execute(&words(&["/system/reboot"])) {
+            Err(errors::MikrotikError::Fatal(msg)) => assert_eq!(msg, "session terminated"),
+            _ => panic!("expected a fatal error"),
+        }
+    }
+
+    #[test]
+    fn test_executor_methods_are_shared() {
+        let port = mock_router(|conn| {
+            let sentence = conn.read();
+            assert_eq!(sentence[..2], words(&["/queue/simple/print", "?name=client1"]));
+
+            let tag = format!(".tag={}", sentence_tag(&sentence).unwrap());
+            conn.reply(&[words(&["!re", "=.id=*1", "=name=client1", &tag]),
+                         words(&["!done", &tag])]);
+
+            let mut buffer = [0; 16];
+            let _ = conn.stream.read(&mut buffer);
+        });
+
+        let client = Client::connect(("127.0.0.1", port)).unwrap();
+        let mux = Multiplexer::new(client).unwrap();
+
+        let query = Query::new().equal("name", "client1");
+        let queues = thread::scope(|s| {
+            s.spawn(|| mux.print_items::<models::Client>(&query)).join().unwrap()
+        });
+
+        assert_eq!(queues.unwrap()[0].id, "*1");
+    }
+}
@@ -0,0 +1,148 @@
+// Query filters the items returned by a print command on the router and selects
+// which of their properties are sent back.
+//
+// Each condition pushes its result on a stack kept by the router, and the stack
+// operations combine the topmost results. Every item for which all the results left
+// on the stack are true is returned.
+//
+//     // Dynamic entries of the 'blocked' or 'suspended' lists, only with their address.
+//     let query = Query::new()
+//         .equal("list", "blocked")
+//         .equal("list", "suspended")
+//         .or()
+//         .equal("dynamic", "true")
+//         .proplist(&["address"]);
+//
+// API documentation:
+//     https://help.mikrotik.com/docs/display/ROS/API#API-Queries
+//
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    proplist: Option<String>,
+    words: Vec<String>,
+}
+
+impl Query {
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    // Pushes true for items that have the property 'key'.
+    pub fn has(mut self, key: &str) -> Query {
+        self.words.push(format!("?{}", key));
+        self
+    }
+
+    // Pushes true for items that do not have the property 'key'.
+    pub fn has_not(mut self, key: &str) -> Query {
+        self.words.push(format!("?-{}", key));
+        self
+    }
+
+    // Pushes true for items whose property 'key' is 'value'.
+    pub fn equal(mut self, key: &str, value: &str) -> Query {
+        self.words.push(format!("?{}={}", key, value));
+        self
+    }
+
+    // Pushes true for items whose property 'key' is greater than 'value'.
+    pub fn greater(mut self, key: &str, value: &str) -> Query {
+        self.words.push(format!("?>{}={}", key, value));
+        self
+    }
+
+    // Pushes true for items whose property 'key' is less than 'value'.
+    pub fn less(mut self, key: &str, value: &str) -> Query {
+        self.words.push(format!("?<{}={}", key, value));
+        self
+    }
+
+    // Replaces the two topmost results with their disjunction.
+    pub fn or(self) -> Query {
+        self.operations("|")
+    }
+
+    // Replaces the two topmost results with their conjunction.
+    pub fn and(self) -> Query {
+        self.operations("&")
+    }
+
+    // Negates the topmost result.
+    pub fn negate(self) -> Query {
+        self.operations("!")
+    }
+
+    // Applies the stack operations in 'ops' from left to right, such as "|!" or
+    // "0&". See the API documentation for the full list.
+    pub fn operations(mut self, ops: &str) -> Query {
+        self.words.push(format!("?#{}", ops));
+        self
+    }
+
+    // Makes the router return only the properties in 'keys'.
+    pub fn proplist(mut self, keys: &[&str]) -> Query {
+        self.proplist = Some(keys.join(","));
+        self
+    }
+
+    // Returns the words to append to a print command.
+    pub fn words(&self) -> Vec<String> {
+        let proplist = self.proplist.iter().map(|p| format!("=.proplist={}", p));
+
+        proplist.chain(self.words.iter().cloned()).collect()
+    }
+
+    // Returns the sentence running 'command' with the query.
+    pub fn sentence(&self, command: &str) -> Vec<String> {
+        let mut sentence = vec![command.to_string()];
+        sentence.extend(self.words());
+
+        sentence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions() {
+        let query = Query::new()
+            .has("comment")
+            .has_not("disabled")
+            .equal("list", "blocked")
+            .greater("rx-byte", "1000")
+            .less("tx-byte", "500");
+
+        assert_eq!(query.words(),
+                   vec!["?comment", "?-disabled", "?list=blocked", "?>rx-byte=1000",
+                        "?<tx-byte=500"]);
+    }
+
+    #[test]
+    fn test_operations() {
+        let query = Query::new()
+            .equal("type", "ether")
+            .equal("type", "vlan")
+            .or()
+            .negate()
+            .has("comment")
+            .and()
+            .operations("0|");
+
+        assert_eq!(query.words(),
+                   vec!["?type=ether", "?type=vlan", "?#|", "?#!", "?comment", "?#&", "?#0|"]);
+    }
+
+    #[test]
+    fn test_sentence_with_proplist() {
+        let query = Query::new().equal("list", "a=b").proplist(&[".id", "address"]);
+
+        assert_eq!(query.sentence("/ip/firewall/address-list/print"),
+                   vec!["/ip/firewall/address-list/print",
+                        "=.proplist=.id,address",
+                        "?list=a=b"]);
+        assert_eq!(Query::new().sentence("/queue/simple/print"),
+                   vec!["/queue/simple/print"]);
+    }
+}
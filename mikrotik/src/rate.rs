@@ -0,0 +1,366 @@
+use crate::resource::Field;
+use crate::types::RosDuration;
+
+use std::fmt;
+use std::time::Duration;
+
+// RateLimit is a pair of bandwidths in bits per second, written by RouterOS as
+// 'upload/download' such as "10M/20M". A value of 0 means unlimited.
+//
+// The upload is the traffic sent by the target, and the download the one it receives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    pub upload: u64,
+    pub download: u64,
+}
+
+impl RateLimit {
+    pub fn new(upload: u64, download: u64) -> RateLimit {
+        RateLimit { upload, download }
+    }
+
+    // Parses a pair such as "10M/20M". A single value, such as "10M", is used for both
+    // directions.
+    pub fn parse(value: &str) -> Option<RateLimit> {
+        let (upload, download) = split_pair(value);
+
+        Some(RateLimit {
+            upload: parse_rate(upload)?,
+            download: parse_rate(download)?,
+        })
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.upload == 0 && self.download == 0
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", format_rate(self.upload), format_rate(self.download))
+    }
+}
+
+impl Field for RateLimit {
+    fn from_value(value: &str) -> Option<RateLimit> {
+        RateLimit::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// Rate is a single bandwidth in bits per second, used where a limit applies to one
+// direction only, such as in queue trees. A value of 0 means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rate(pub u64);
+
+impl Rate {
+    pub fn parse(value: &str) -> Option<Rate> {
+        parse_rate(value).map(Rate)
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&format_rate(self.0))
+    }
+}
+
+impl From<u64> for Rate {
+    fn from(rate: u64) -> Rate {
+        Rate(rate)
+    }
+}
+
+impl Field for Rate {
+    fn from_value(value: &str) -> Option<Rate> {
+        Rate::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// BurstTime is the period over which the average rate of a queue is measured to allow
+// a burst, for upload and download, written by RouterOS as "8s/8s".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BurstTime {
+    pub upload: Duration,
+    pub download: Duration,
+}
+
+impl BurstTime {
+    pub fn new(upload: Duration, download: Duration) -> BurstTime {
+        BurstTime { upload, download }
+    }
+
+    // Parses a pair such as "8s/8s", with each time in RouterOS duration notation. A
+    // single value is used for both directions.
+    pub fn parse(value: &str) -> Option<BurstTime> {
+        let (upload, download) = split_pair(value);
+
+        Some(BurstTime {
+            upload: RosDuration::parse(upload)?.into(),
+            download: RosDuration::parse(download)?.into(),
+        })
+    }
+}
+
+impl fmt::Display for BurstTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", RosDuration(self.upload), RosDuration(self.download))
+    }
+}
+
+impl Field for BurstTime {
+    fn from_value(value: &str) -> Option<BurstTime> {
+        BurstTime::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// ProfileRateLimit is the 'rate-limit' of a PPP or hotspot profile, from which the
+// router creates a simple queue for each session. It is written as
+//
+//     rx/tx [burst-rx/burst-tx [threshold-rx/threshold-tx [time-rx/time-tx
+//         [priority [min-rx/min-tx]]]]]
+//
+// such as "10M/20M 15M/25M 8M/16M 8/8 8 5M/10M", where rx is what the router receives
+// from the client, the upload of a simple queue. Every part after the rate is
+// optional. Burst times are whole seconds, so parsing rejects any finer one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ProfileRateLimit {
+    pub rate: RateLimit,
+    pub burst_limit: Option<RateLimit>,
+    pub burst_threshold: Option<RateLimit>,
+    pub burst_time: Option<BurstTime>,
+    pub priority: Option<u8>,
+    pub limit_at: Option<RateLimit>,
+}
+
+impl ProfileRateLimit {
+    pub fn new(rate: RateLimit) -> ProfileRateLimit {
+        ProfileRateLimit { rate, ..ProfileRateLimit::default() }
+    }
+
+    pub fn parse(value: &str) -> Option<ProfileRateLimit> {
+        let mut parts = value.split_whitespace();
+        let mut limit = ProfileRateLimit::new(RateLimit::parse(parts.next()?)?);
+
+        if let Some(part) = parts.next() {
+            limit.burst_limit = Some(RateLimit::parse(part)?);
+        }
+        if let Some(part) = parts.next() {
+            limit.burst_threshold = Some(RateLimit::parse(part)?);
+        }
+        if let Some(part) = parts.next() {
+            let time = BurstTime::parse(part)?;
+            if time.upload.subsec_nanos() != 0 || time.download.subsec_nanos() != 0 {
+                return None;
+            }
+            limit.burst_time = Some(time);
+        }
+        if let Some(part) = parts.next() {
+            limit.priority = Some(part.parse().ok()?);
+        }
+        if let Some(part) = parts.next() {
+            limit.limit_at = Some(RateLimit::parse(part)?);
+        }
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(limit)
+    }
+}
+
+// Parts left out before one that is set are written with the defaults of the router.
+impl fmt::Display for ProfileRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let burst_time = self.burst_time.map(|t| {
+            format!("{}/{}", t.upload.as_secs(), t.download.as_secs())
+        });
+        let optional = [
+            self.burst_limit.map(|l| l.to_string()),
+            self.burst_threshold.map(|l| l.to_string()),
+            burst_time,
+            self.priority.map(|p| p.to_string()),
+            self.limit_at.map(|l| l.to_string()),
+        ];
+        let defaults = ["0/0", "0/0", "0/0", "8", "0/0"];
+        let count = optional.iter().rposition(Option::is_some).map_or(0, |i| i + 1);
+
+        write!(f, "{}", self.rate)?;
+        for (part, default) in optional.iter().zip(defaults.iter()).take(count) {
+            write!(f, " {}", part.as_deref().unwrap_or(default))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Field for ProfileRateLimit {
+    fn from_value(value: &str) -> Option<ProfileRateLimit> {
+        ProfileRateLimit::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+fn split_pair(value: &str) -> (&str, &str) {
+    value.split_once('/').unwrap_or((value, value))
+}
+
+const RATE_UNITS: [(char, u64); 3] = [('G', 1_000_000_000), ('M', 1_000_000), ('k', 1_000)];
+
+// Parses a rate in bits per second such as "512k", "1.5M" or "64000". Suffixes are
+// decimal, as in RouterOS, and case insensitive.
+pub fn parse_rate(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            let unit = RATE_UNITS.iter().find(|(u, _)| u.eq_ignore_ascii_case(&c))?;
+            (&value[..value.len() - 1], unit.1)
+        }
+        _ => (value, 1),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) ||
+       !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut rate = whole.parse::<u64>().ok()?.checked_mul(multiplier)?;
+    let mut scale = multiplier;
+
+    // Digits beyond the precision of the unit are dropped, as they are below 1 bit/s.
+    for digit in fraction.bytes() {
+        scale /= 10;
+        rate = rate.checked_add(u64::from(digit - b'0') * scale)?;
+    }
+
+    Some(rate)
+}
+
+// Formats a rate in bits per second with the largest suffix that keeps it exact, such
+// as "20M" for 20000000 or "1500k" for 1500000.
+pub fn format_rate(rate: u64) -> String {
+    if rate > 0 {
+        for &(unit, multiplier) in RATE_UNITS.iter() {
+            if rate.is_multiple_of(multiplier) {
+                return format!("{}{}", rate / multiplier, unit);
+            }
+        }
+    }
+
+    rate.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("0"), Some(0));
+        assert_eq!(parse_rate("64000"), Some(64_000));
+        assert_eq!(parse_rate("512k"), Some(512_000));
+        assert_eq!(parse_rate("512K"), Some(512_000));
+        assert_eq!(parse_rate("10M"), Some(10_000_000));
+        assert_eq!(parse_rate("1.5M"), Some(1_500_000));
+        assert_eq!(parse_rate("2G"), Some(2_000_000_000));
+        assert_eq!(parse_rate(""), None);
+        assert_eq!(parse_rate("M"), None);
+        assert_eq!(parse_rate("10T"), None);
+        assert_eq!(parse_rate("-1M"), None);
+        assert_eq!(parse_rate("1.M5"), None);
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(0), "0");
+        assert_eq!(format_rate(64_500), "64500");
+        assert_eq!(format_rate(512_000), "512k");
+        assert_eq!(format_rate(1_500_000), "1500k");
+        assert_eq!(format_rate(20_000_000), "20M");
+        assert_eq!(format_rate(1_000_000_000), "1G");
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limit = RateLimit::parse("10M/20M").unwrap();
+
+        assert_eq!(limit, RateLimit::new(10_000_000, 20_000_000));
+        assert_eq!(limit.to_string(), "10M/20M");
+        assert_eq!(RateLimit::parse("1.5M").unwrap(), RateLimit::new(1_500_000, 1_500_000));
+        assert_eq!(RateLimit::parse("0/0").unwrap().to_string(), "0/0");
+        assert!(RateLimit::parse("0/0").unwrap().is_unlimited());
+        assert_eq!(RateLimit::parse("10M/"), None);
+        assert!(limit.download > RateLimit::parse("15M/15M").unwrap().download);
+    }
+
+    #[test]
+    fn test_rate() {
+        assert_eq!(Rate::parse("10000000"), Some(Rate(10_000_000)));
+        assert_eq!(Rate::parse("2M").unwrap().to_string(), "2M");
+        assert!(Rate::parse("2M").unwrap() > Rate::parse("1500k").unwrap());
+        assert_eq!(Rate::parse("2M/2M"), None);
+    }
+
+    #[test]
+    fn test_burst_time() {
+        let time = BurstTime::parse("8s/16s").unwrap();
+
+        assert_eq!(time, BurstTime::new(Duration::from_secs(8), Duration::from_secs(16)));
+        assert_eq!(time.to_string(), "8s/16s");
+        assert_eq!(BurstTime::parse("0s").unwrap().to_string(), "0s/0s");
+        assert_eq!(BurstTime::parse("500ms/2").unwrap().to_string(), "500ms/2s");
+        assert_eq!(BurstTime::parse("1m30s/00:01:30").unwrap().to_string(), "1m30s/1m30s");
+        assert_eq!(BurstTime::parse("8x/8s"), None);
+    }
+
+    #[test]
+    fn test_profile_rate_limit() {
+        let limit = ProfileRateLimit::parse("10M/20M 15M/25M 8M/16M 8/8 8 5M/10M").unwrap();
+
+        assert_eq!(limit.rate, RateLimit::new(10_000_000, 20_000_000));
+        assert_eq!(limit.burst_limit, RateLimit::parse("15M/25M"));
+        assert_eq!(limit.burst_threshold, RateLimit::parse("8M/16M"));
+        assert_eq!(limit.burst_time, BurstTime::parse("8s/8s"));
+        assert_eq!(limit.priority, Some(8));
+        assert_eq!(limit.limit_at, RateLimit::parse("5M/10M"));
+        assert_eq!(limit.to_string(), "10M/20M 15M/25M 8M/16M 8/8 8 5M/10M");
+
+        let limit = ProfileRateLimit::parse("512k/2M").unwrap();
+        assert_eq!(limit, ProfileRateLimit::new(RateLimit::new(512_000, 2_000_000)));
+        assert_eq!(limit.to_string(), "512k/2M");
+
+        let limit = ProfileRateLimit { priority: Some(1), ..limit };
+        assert_eq!(limit.to_string(), "512k/2M 0/0 0/0 0/0 1");
+
+        assert_eq!(ProfileRateLimit::parse(""), None);
+        assert_eq!(ProfileRateLimit::parse("1M/1M 2M/2M 1M/1M 8/8 high"), None);
+        assert_eq!(ProfileRateLimit::parse("1M/1M 0/0 0/0 0/0 8 0/0 extra"), None);
+    }
+
+    #[test]
+    fn test_profile_burst_time() {
+        let limit = ProfileRateLimit::parse("1M/2M 2M/4M 1M/2M 1m/30s").unwrap();
+        assert_eq!(limit.burst_time,
+                   Some(BurstTime::new(Duration::from_secs(60), Duration::from_secs(30))));
+        assert_eq!(limit.to_string(), "1M/2M 2M/4M 1M/2M 60/30");
+        assert_eq!(ProfileRateLimit::parse(&limit.to_string()), Some(limit));
+
+        assert_eq!(ProfileRateLimit::parse("1M/2M 2M/4M 1M/2M 500ms/500ms"), None);
+        assert_eq!(ProfileRateLimit::parse("1M/2M 2M/4M 1M/2M 8/1500ms"), None);
+    }
+}
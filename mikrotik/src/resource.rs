@@ -0,0 +1,297 @@
+use crate::errors::MikrotikError;
+use crate::query::Query;
+use crate::sentence::{Reply, Sentence};
+use crate::types::RosBool;
+
+// Resource is an item of a RouterOS menu, such as an entry of
+// '/ip/firewall/address-list', that can be printed, added, set and removed with the
+// generic methods of Executor.
+//
+// It is usually derived, mapping every field to the attribute of the same name:
+//
+//     #[derive(Default, Resource)]
+//     #[resource(menu = "/ip/firewall/address-list")]
+//     pub struct Entry {
+//         #[resource(id)]
+//         pub id: String,
+//         pub list: String,
+//         pub address: String,
+//         #[resource(read_only)]
+//         pub dynamic: bool,
+//     }
+//
+// See the mikrotik-derive crate for every field option.
+pub trait Resource: Sized {
+    // Returns the path of the menu the items live in, such as "/queue/simple".
+    fn menu() -> &'static str;
+
+    // Builds an item from one of the '!re' replies to the print command of the menu.
+    fn from_sentence(sentence: &Sentence) -> Result<Self, MikrotikError>;
+
+    // Returns the attributes sent when the item is added or set, without its id.
+    fn attributes(&self) -> Vec<(String, String)>;
+
+    // Returns the '.id' of the item, for menus that have one.
+    fn id(&self) -> Option<&str> {
+        None
+    }
+}
+
+// Field is a type a Resource field can have, converting from and to the text of an
+// attribute value.
+pub trait Field: Sized {
+    // Returns None if 'value' is not valid for the type.
+    fn from_value(value: &str) -> Option<Self>;
+
+    // Returns None if the field should not be sent.
+    fn to_value(&self) -> Option<String>;
+}
+
+// Empty strings are not sent, so fields the router fills in by default can be left
+// empty. Use an Option<String> holding an empty string to clear a value.
+impl Field for String {
+    fn from_value(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    fn to_value(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(self.clone())
+    }
+}
+
+impl Field for bool {
+    fn from_value(value: &str) -> Option<bool> {
+        RosBool::parse(value).map(bool::from)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+macro_rules! impl_field_for_number {
+    ($($t:ty),*) => {
+        $(
+            impl Field for $t {
+                fn from_value(value: &str) -> Option<$t> {
+                    value.parse().ok()
+                }
+
+                fn to_value(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    }
+}
+
+impl_field_for_number!(u8, u16, u32, u64, i32, i64, f32, f64);
+
+// Optional fields are left out of the reply by the router when they are not set, and
+// are only sent when they hold a value. A value is sent even if T would leave it out,
+// so Some(String::new()) clears the attribute. An empty value that is not valid for
+// T, such as the 'mac-address' of some tunnels, is read as None too.
+impl<T: Field> Field for Option<T> {
+    fn from_value(value: &str) -> Option<Option<T>> {
+        match T::from_value(value) {
+            Some(value) => Some(Some(value)),
+            None if value.is_empty() => Some(None),
+            None => None,
+        }
+    }
+
+    fn to_value(&self) -> Option<String> {
+        self.as_ref().map(|v| v.to_value().unwrap_or_default())
+    }
+}
+
+// Parses the attribute 'key' of 'sentence', leaving the field with its default value
+// when the router did not send it.
+pub fn parse_field<T>(sentence: &Sentence, key: &str) -> Result<T, MikrotikError>
+    where T: Field + Default
+{
+    match sentence.get(key) {
+        Some(value) => {
+            T::from_value(value).ok_or_else(|| {
+                let msg = format!("invalid value '{}' for '{}'", value, key);
+                MikrotikError::UnexpectedReply(msg)
+            })
+        }
+        None => Ok(T::default()),
+    }
+}
+
+fn command<T: Resource>(action: &str) -> String {
+    format!("{}/{}", T::menu().trim_end_matches('/'), action)
+}
+
+pub(crate) fn print_sentence<T: Resource>(query: &Query) -> Vec<String> {
+    query.sentence(&command::<T>("print"))
+}
+
+// Builds an item from every '!re' reply to a print command.
+pub(crate) fn parse_items<T: Resource>(response: &[Sentence]) -> Result<Vec<T>, MikrotikError> {
+    response.iter()
+        .filter(|s| s.reply == Reply::Re)
+        .map(T::from_sentence)
+        .collect()
+}
+
+fn attribute_words<T: Resource>(item: &T) -> impl Iterator<Item = String> {
+    item.attributes().into_iter().map(|(key, value)| format!("={}={}", key, value))
+}
+
+pub(crate) fn add_sentence<T: Resource>(item: &T) -> Vec<String> {
+    let mut sentence = vec![command::<T>("add")];
+    sentence.extend(attribute_words(item));
+
+    sentence
+}
+
+pub(crate) fn set_sentence<T: Resource>(item: &T) -> Result<Vec<String>, MikrotikError> {
+    let id = item.id().ok_or_else(|| {
+        let msg = format!("items of {} can not be set without an id", T::menu());
+        MikrotikError::UnexpectedReply(msg)
+    })?;
+
+    let mut sentence = vec![command::<T>("set"), format!("=.id={}", id)];
+    sentence.extend(attribute_words(item));
+
+    Ok(sentence)
+}
+
+// Returns the sentence running 'action' on the item with 'id', such as "remove" or
+// "disable".
+pub(crate) fn item_sentence<T: Resource>(action: &str, id: &str) -> Vec<String> {
+    vec![command::<T>(action), format!("=.id={}", id)]
+}
+
+// Returns the sentence moving the item with 'id' before the one with 'before', or to
+// the end of the menu.
+pub(crate) fn move_sentence<T: Resource>(id: &str, before: Option<&str>) -> Vec<String> {
+    let mut sentence = vec![command::<T>("move"), format!("=numbers={}", id)];
+    sentence.extend(before.map(|b| format!("=destination={}", b)));
+
+    sentence
+}
+
+// Returns the only item of menus holding a single one, such as "/system/resource".
+pub(crate) fn single_item<T: Resource>(items: Vec<T>) -> Result<T, MikrotikError> {
+    items.into_iter().next().ok_or_else(|| {
+        MikrotikError::UnexpectedReply(format!("no reply to {}/print", T::menu()))
+    })
+}
+
+// Returns the id the router assigned to an added item, sent as '=ret=' in '!done'.
+pub(crate) fn added_id(response: &[Sentence]) -> Result<String, MikrotikError> {
+    match response.iter().find_map(|s| s.get("ret")) {
+        Some(id) => Ok(id.to_string()),
+        None => Err(MikrotikError::UnexpectedReply(String::from("no id in reply to add"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::words;
+
+    #[derive(Debug, Default, PartialEq, crate::Resource)]
+    #[resource(menu = "/interface/vlan")]
+    struct Vlan {
+        #[resource(id)]
+        id: String,
+        name: String,
+        vlan_id: u16,
+        #[resource(rename = "interface")]
+        parent: String,
+        #[resource(read_only)]
+        running: bool,
+        comment: Option<String>,
+    }
+
+    #[test]
+    fn test_from_sentence() {
+        let sentence = Sentence::parse(&words(&["!re",
+                                                "=.id=*3",
+                                                "=name=vlan10",
+                                                "=vlan-id=10",
+                                                "=interface=ether1",
+                                                "=running=true"]))
+            .unwrap();
+
+        assert_eq!(Vlan::from_sentence(&sentence).unwrap(),
+                   Vlan {
+                       id: String::from("*3"),
+                       name: String::from("vlan10"),
+                       vlan_id: 10,
+                       parent: String::from("ether1"),
+                       running: true,
+                       comment: None,
+                   });
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let sentence = Sentence::parse(&words(&["!re", "=vlan-id=ten"])).unwrap();
+
+        match Vlan::from_sentence(&sentence) {
+            Err(MikrotikError::UnexpectedReply(msg)) => {
+                assert_eq!(msg, "invalid value 'ten' for 'vlan-id'")
+            }
+            _ => panic!("expected an unexpected reply error"),
+        }
+    }
+
+    #[test]
+    fn test_sentences() {
+        let vlan = Vlan {
+            id: String::from("*3"),
+            name: String::from("vlan10"),
+            vlan_id: 10,
+            parent: String::from("ether1"),
+            running: true,
+            comment: Some(String::from("office")),
+        };
+
+        assert_eq!(add_sentence(&vlan),
+                   vec!["/interface/vlan/add",
+                        "=name=vlan10",
+                        "=vlan-id=10",
+                        "=interface=ether1",
+                        "=comment=office"]);
+        assert_eq!(set_sentence(&vlan).unwrap(),
+                   vec!["/interface/vlan/set",
+                        "=.id=*3",
+                        "=name=vlan10",
+                        "=vlan-id=10",
+                        "=interface=ether1",
+                        "=comment=office"]);
+        assert_eq!(item_sentence::<Vlan>("remove", "*3"),
+                   vec!["/interface/vlan/remove", "=.id=*3"]);
+        assert_eq!(move_sentence::<Vlan>("*3", Some("*1")),
+                   vec!["/interface/vlan/move", "=numbers=*3", "=destination=*1"]);
+        assert_eq!(move_sentence::<Vlan>("*3", None), vec!["/interface/vlan/move", "=numbers=*3"]);
+        assert_eq!(print_sentence::<Vlan>(&Query::new().has("comment")),
+                   vec!["/interface/vlan/print", "?comment"]);
+    }
+
+    #[test]
+    fn test_clear_optional_field() {
+        let mut vlan = Vlan {
+            id: String::from("*3"),
+            comment: Some(String::new()),
+            ..Vlan::default()
+        };
+
+        assert_eq!(set_sentence(&vlan).unwrap(),
+                   vec!["/interface/vlan/set", "=.id=*3", "=vlan-id=0", "=comment="]);
+
+        vlan.comment = None;
+        assert_eq!(set_sentence(&vlan).unwrap(),
+                   vec!["/interface/vlan/set", "=.id=*3", "=vlan-id=0"]);
+    }
+}
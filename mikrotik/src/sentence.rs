@@ -0,0 +1,240 @@
+use crate::errors::MikrotikError;
+
+use std::collections::BTreeMap;
+
+// Reply is the kind of a sentence sent by the router, given by its first word.
+//
+// Empty is sent by RouterOS 7 instead of any '!re' when a command has nothing to
+// return, and is followed by '!done' like the rest.
+//
+// API documentation:
+//     https://help.mikrotik.com/docs/display/ROS/API#API-Replies
+//
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reply {
+    Re,
+    Done,
+    Trap,
+    Fatal,
+    Empty,
+}
+
+impl Reply {
+    // Returns the reply for a reply word such as '!re', if it is a known one.
+    pub fn from_word(word: &str) -> Option<Reply> {
+        match word {
+            "!re" => Some(Reply::Re),
+            "!done" => Some(Reply::Done),
+            "!trap" => Some(Reply::Trap),
+            "!fatal" => Some(Reply::Fatal),
+            "!empty" => Some(Reply::Empty),
+            _ => None,
+        }
+    }
+
+    pub fn word(&self) -> &'static str {
+        match *self {
+            Reply::Re => "!re",
+            Reply::Done => "!done",
+            Reply::Trap => "!trap",
+            Reply::Fatal => "!fatal",
+            Reply::Empty => "!empty",
+        }
+    }
+}
+
+// Sentence is a reply from the router with its words parsed.
+//
+// 'attributes' maps the key of every '=key=value' word to its value, including API
+// attributes such as '.id' and '.section'. 'tag' is the value of the '.tag=' word the
+// reply was sent with, if any.
+//
+// The reason given in a '!fatal' sentence is not an attribute word, so it is stored
+// as its 'message' attribute, like the one of a '!trap'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sentence {
+    pub reply: Reply,
+    pub attributes: BTreeMap<String, String>,
+    pub tag: Option<String>,
+}
+
+impl Sentence {
+    pub fn new(reply: Reply) -> Sentence {
+        Sentence {
+            reply,
+            attributes: BTreeMap::new(),
+            tag: None,
+        }
+    }
+
+    // Parses the words of a sentence read from the router.
+    pub fn parse(words: &[String]) -> Result<Sentence, MikrotikError> {
+        let first = match words.first() {
+            Some(word) => word,
+            None => return Err(MikrotikError::UnexpectedReply(String::from("empty sentence"))),
+        };
+
+        let reply = match Reply::from_word(first) {
+            Some(reply) => reply,
+            None => {
+                let msg = format!("unknown reply word '{}'", first);
+                return Err(MikrotikError::UnexpectedReply(msg));
+            }
+        };
+
+        let mut sentence = Sentence::new(reply);
+
+        for word in &words[1..] {
+            if let Some(tag) = word.strip_prefix(".tag=") {
+                sentence.tag = Some(tag.to_string());
+            } else if let Some((key, value)) = parse_attribute(word) {
+                sentence.attributes.insert(key.to_string(), value.to_string());
+            } else if reply == Reply::Fatal && !word.starts_with('=') {
+                sentence.attributes.insert(String::from("message"), word.clone());
+            }
+        }
+
+        Ok(sentence)
+    }
+
+    // Returns the value of the attribute 'key', given without the surrounding '='.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|v| v.as_str())
+    }
+
+    // Returns the internal id of the item the sentence describes, such as '*1A'.
+    pub fn id(&self) -> Option<&str> {
+        self.get(".id")
+    }
+
+    // Returns the section of the item in commands that group their output, such as
+    // '/ip/firewall/filter/print' with 'chain' sections.
+    pub fn section(&self) -> Option<&str> {
+        self.get(".section")
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.get("message")
+    }
+
+    // Returns the error a '!trap' or '!fatal' sentence stands for.
+    pub fn error(&self) -> Option<MikrotikError> {
+        match self.reply {
+            Reply::Trap => {
+                // Traps without a category are reported with one no RouterOS uses.
+                let category = self.get("category")
+                    .and_then(|c| c.parse::<u8>().ok())
+                    .unwrap_or(10);
+                let msg = self.message().unwrap_or_default().to_string();
+
+                Some(MikrotikError::Trap { category, msg })
+            }
+            Reply::Fatal => {
+                let msg = self.message()
+                    .unwrap_or("No error message supplied from router!")
+                    .to_string();
+
+                Some(MikrotikError::Fatal(msg))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Splits an attribute word such as '=comment=a=b' into its key and value.
+//
+// The key ends at the first '=' after the leading one, so the value is kept whole
+// even when it contains '=' or the key itself. API attributes keep their leading dot,
+// as in '=.id=*1' or '=.proplist=name,address'. A word without a value, such as
+// '=disabled', has an empty one.
+//
+// Returns None for words that are not attributes or have an empty key.
+pub fn parse_attribute(word: &str) -> Option<(&str, &str)> {
+    let attribute = word.strip_prefix('=')?;
+    let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+
+    if key.is_empty() {
+        return None;
+    }
+
+    Some((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::words;
+
+    #[test]
+    fn test_parse_re() {
+        let sentence = Sentence::parse(&words(&["!re",
+                                                "=.id=*1A",
+                                                "=.section=0",
+                                                "=name=ether1",
+                                                "=comment=",
+                                                ".tag=7"]))
+            .unwrap();
+
+        assert_eq!(sentence.reply, Reply::Re);
+        assert_eq!(sentence.id(), Some("*1A"));
+        assert_eq!(sentence.section(), Some("0"));
+        assert_eq!(sentence.get("name"), Some("ether1"));
+        assert_eq!(sentence.get("comment"), Some(""));
+        assert_eq!(sentence.get("missing"), None);
+        assert_eq!(sentence.tag.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn test_parse_attribute() {
+        assert_eq!(parse_attribute("=name=ether1"), Some(("name", "ether1")));
+        assert_eq!(parse_attribute("=comment="), Some(("comment", "")));
+        assert_eq!(parse_attribute("=disabled"), Some(("disabled", "")));
+        assert_eq!(parse_attribute("=.id=*1A"), Some((".id", "*1A")));
+        assert_eq!(parse_attribute("=.proplist=name,address"),
+                   Some((".proplist", "name,address")));
+        assert_eq!(parse_attribute("==value"), None);
+        assert_eq!(parse_attribute("!re"), None);
+        assert_eq!(parse_attribute(".tag=1"), None);
+    }
+
+    #[test]
+    fn test_parse_attribute_value_with_equal_signs() {
+        assert_eq!(parse_attribute("=comment=a=b==c="), Some(("comment", "a=b==c=")));
+        assert_eq!(parse_attribute("=list=list=blocked"), Some(("list", "list=blocked")));
+        assert_eq!(parse_attribute("=source=:put \"=x=\""), Some(("source", ":put \"=x=\"")));
+        assert_eq!(parse_attribute("=url=https://host/?a=1&b=2"),
+                   Some(("url", "https://host/?a=1&b=2")));
+    }
+
+    #[test]
+    fn test_trap_error() {
+        let sentence = Sentence::parse(&words(&["!trap",
+                                                "=category=1",
+                                                "=message=invalid value"]))
+            .unwrap();
+
+        match sentence.error() {
+            Some(MikrotikError::Trap { category, msg }) => {
+                assert_eq!(category, 1);
+                assert_eq!(msg, "invalid value");
+            }
+            _ => panic!("expected a trap error"),
+        }
+    }
+
+    #[test]
+    fn test_fatal_message() {
+        let sentence = Sentence::parse(&words(&["!fatal", "session terminated"])).unwrap();
+
+        assert_eq!(sentence.reply, Reply::Fatal);
+        assert_eq!(sentence.message(), Some("session terminated"));
+        assert!(matches!(sentence.error(), Some(MikrotikError::Fatal(_))));
+    }
+
+    #[test]
+    fn test_parse_unknown_reply() {
+        assert!(Sentence::parse(&words(&["!unknown"])).is_err());
+        assert!(Sentence::parse(&[]).is_err());
+        assert_eq!(Sentence::parse(&words(&["!empty"])).unwrap().reply, Reply::Empty);
+    }
+}
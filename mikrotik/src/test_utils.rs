@@ -0,0 +1,73 @@
+// Helpers to script a fake router in tests.
+
+use crate::client;
+use crate::codec;
+use crate::encoding::Encoding;
+
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+pub(crate) fn words(w: &[&str]) -> Vec<String> {
+    w.iter().map(|x| x.to_string()).collect()
+}
+
+// Returns the value of the '.tag=' attribute of a sentence sent by the client.
+pub(crate) fn sentence_tag(sentence: &[String]) -> Option<&str> {
+    sentence.iter()
+        .find(|w| w.starts_with(".tag="))
+        .map(|w| &w[".tag=".len()..])
+}
+
+// MockConnection is the router side of a connection accepted by mock_router.
+pub(crate) struct MockConnection {
+    pub(crate) stream: TcpStream,
+    decoder: codec::Decoder,
+}
+
+impl MockConnection {
+    // Reads the next sentence sent by the client.
+    pub(crate) fn read(&mut self) -> Vec<String> {
+        Encoding::Utf8.decode_words(&self.read_words())
+    }
+
+    pub(crate) fn read_words(&mut self) -> Vec<Vec<u8>> {
+        client::read_words(&mut self.stream, &mut self.decoder).unwrap()
+    }
+
+    pub(crate) fn reply<W: AsRef<[u8]>>(&mut self, sentences: &[Vec<W>]) {
+        let mut out = Vec::new();
+        for s in sentences {
+            codec::encode_sentence(s, &mut out).unwrap();
+        }
+
+        self.stream.write_all(&out).unwrap();
+    }
+
+    // Reads a sentence, checks it is 'expected' and answers it with 'sentences'.
+    pub(crate) fn expect(&mut self, expected: &[&str], sentences: &[Vec<String>]) {
+        assert_eq!(self.read(), words(expected));
+        self.reply(sentences);
+    }
+}
+
+// Runs 'script' on a background thread against the first connection made to the
+// returned port.
+pub(crate) fn mock_router<F>(script: F) -> u16
+    where F: FnOnce(&mut MockConnection) + Send + 'static
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut conn = MockConnection {
+            stream,
+            decoder: codec::Decoder::new(),
+        };
+
+        script(&mut conn);
+    });
+
+    port
+}
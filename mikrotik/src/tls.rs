@@ -0,0 +1,301 @@
+use crate::errors::CreateClientError;
+
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslStream, SslVerifyMode, SslVersion};
+
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+
+// Anonymous Diffie-Hellman ciphers offered by RouterOS when the api-ssl service has
+// no certificate assigned. OpenSSL refuses them at its default security level, and
+// they only exist up to TLS 1.2.
+const ANONYMOUS_CIPHERS: &str = "ADH-AES256-GCM-SHA384:ADH-AES128-GCM-SHA256:\
+                                 ADH-AES256-SHA256:ADH-AES128-SHA256:ADH-AES256-SHA:\
+                                 ADH-AES128-SHA:@SECLEVEL=0";
+
+// TlsOptions configures how Client::connect_tls sets up the TLS session with the
+// api-ssl service.
+//
+// By default the router certificate must be signed by one of the system trusted
+// CAs and issued for the host the router was reached at: its IP address for routers
+// reached by address, or its name otherwise. Use 'domain' when the certificate names
+// a router reached by IP address.
+//
+// RouterOS documentation:
+//     https://wiki.mikrotik.com/wiki/Manual:API-SSL
+//
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    ca_file: Option<PathBuf>,
+    certificate: Option<(PathBuf, PathBuf)>,
+    domain: Option<String>,
+    accept_invalid_hostnames: bool,
+    anonymous: bool,
+}
+
+impl TlsOptions {
+    pub fn new() -> TlsOptions {
+        TlsOptions::default()
+    }
+
+    // Trusts the CA certificates in the PEM bundle at 'path' in addition to the
+    // system ones.
+    pub fn ca_file<P: AsRef<Path>>(mut self, path: P) -> TlsOptions {
+        self.ca_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    // Presents the PEM certificate chain at 'cert' with its private key at 'key' to
+    // routers that require client certificates.
+    pub fn client_certificate<P, Q>(mut self, cert: P, key: Q) -> TlsOptions
+        where P: AsRef<Path>,
+              Q: AsRef<Path>
+    {
+        self.certificate = Some((cert.as_ref().to_path_buf(), key.as_ref().to_path_buf()));
+        self
+    }
+
+    // Verifies that the router certificate was issued for 'domain'.
+    pub fn domain(mut self, domain: &str) -> TlsOptions {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    // Accepts a trusted certificate issued for any host.
+    //
+    // Any router, or anyone else, holding a certificate from a trusted CA can then
+    // impersonate the router, so this is only safe with a private CA given with
+    // 'ca_file' that signs nothing but the certificates of your own routers.
+    pub fn accept_invalid_hostnames(mut self) -> TlsOptions {
+        self.accept_invalid_hostnames = true;
+        self
+    }
+
+    // Uses the anonymous Diffie-Hellman ciphers RouterOS falls back to when no
+    // certificate is assigned to the api-ssl service.
+    //
+    // The session is encrypted but the router is not authenticated, so it offers no
+    // protection against an active man in the middle.
+    pub fn anonymous(mut self) -> TlsOptions {
+        self.anonymous = true;
+        self
+    }
+}
+
+// Performs the TLS handshake over 'stream'. 'host' is the address or name the router
+// was reached at, which the certificate must be issued for unless 'options' says
+// otherwise.
+pub(crate) fn connect(stream: TcpStream,
+                      host: &str,
+                      options: &TlsOptions)
+                      -> Result<SslStream<TcpStream>, CreateClientError> {
+    let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+
+    if options.anonymous {
+        builder.set_cipher_list(ANONYMOUS_CIPHERS)?;
+        builder.set_max_proto_version(Some(SslVersion::TLS1_2))?;
+        builder.set_verify(SslVerifyMode::NONE);
+    }
+
+    if let Some(ref path) = options.ca_file {
+        builder.set_ca_file(path)?;
+    }
+
+    if let Some((ref cert, ref key)) = options.certificate {
+        builder.set_certificate_chain_file(cert)?;
+        builder.set_private_key_file(key, SslFiletype::PEM)?;
+        builder.check_private_key()?;
+    }
+
+    let mut config = builder.build().configure()?;
+    config.set_verify_hostname(!options.accept_invalid_hostnames);
+
+    // An IP address is checked against the IP addresses the certificate lists, and is
+    // not sent as the server name.
+    let domain = options.domain.as_deref().unwrap_or(host);
+
+    Ok(config.connect(domain, stream)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{self, Client};
+    use crate::codec;
+
+    use openssl::asn1::Asn1Time;
+    use openssl::dh::Dh;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::ssl::{SslAcceptor, SslAcceptorBuilder};
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::{X509NameBuilder, X509};
+
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    static NEXT_PEM: AtomicUsize = AtomicUsize::new(0);
+
+    // PEM files of a test certificate, removed from disk when dropped.
+    struct PemFiles {
+        cert: PathBuf,
+        key: PathBuf,
+    }
+
+    impl Drop for PemFiles {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.cert);
+            let _ = fs::remove_file(&self.key);
+        }
+    }
+
+    // Creates a self signed certificate for 'router.test' and 127.0.0.1 and writes it
+    // along with its key under unique names in the temporary directory.
+    fn self_signed(name: &str) -> (X509, PKey<Private>, PemFiles) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_text("CN", "router.test").unwrap();
+        let subject = subject.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&subject).unwrap();
+        builder.set_issuer_name(&subject).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns("router.test")
+            .ip("127.0.0.1")
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = builder.build();
+
+        let dir = env::temp_dir();
+        let n = NEXT_PEM.fetch_add(1, Ordering::SeqCst);
+        let prefix = format!("mikrotik-tls-{}-{}-{}", process::id(), n, name);
+        let files = PemFiles {
+            cert: dir.join(format!("{}.crt", prefix)),
+            key: dir.join(format!("{}.key", prefix)),
+        };
+        fs::write(&files.cert, cert.to_pem().unwrap()).unwrap();
+        fs::write(&files.key, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+        (cert, key, files)
+    }
+
+    // Mock api-ssl service that answers a single sentence with '!done'.
+    fn mock_router(acceptor: SslAcceptor) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut stream = match acceptor.accept(stream) {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+            let mut decoder = codec::Decoder::new();
+
+            let request = client::read_words(&mut stream, &mut decoder).unwrap();
+            assert_eq!(request, vec![b"/system/identity/print"]);
+
+            let mut out = Vec::new();
+            codec::encode_sentence(&["!re", "=name=router"], &mut out).unwrap();
+            codec::encode_sentence(&["!done"], &mut out).unwrap();
+            stream.write_all(&out).unwrap();
+        });
+
+        port
+    }
+
+    fn acceptor(cert: &X509, key: &PKey<Private>) -> SslAcceptorBuilder {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        builder.set_certificate(cert).unwrap();
+        builder.set_private_key(key).unwrap();
+
+        builder
+    }
+
+    fn identity(client: &mut Client) -> String {
+        let response = client.execute(&["/system/identity/print".to_string()]).unwrap();
+        response[0].get("name").unwrap().to_string()
+    }
+
+    #[test]
+    fn test_connect_with_custom_ca() {
+        let (cert, key, files) = self_signed("ca");
+        let port = mock_router(acceptor(&cert, &key).build());
+
+        let options = TlsOptions::new().ca_file(&files.cert).domain("router.test");
+        let mut client = Client::connect_tls(("127.0.0.1", port), &options).unwrap();
+
+        assert_eq!(identity(&mut client), "router");
+    }
+
+    #[test]
+    fn test_untrusted_certificate_is_rejected() {
+        let (cert, key, _files) = self_signed("untrusted");
+        let port = mock_router(acceptor(&cert, &key).build());
+
+        let options = TlsOptions::new();
+        let result = Client::connect_tls(("127.0.0.1", port), &options);
+
+        assert!(matches!(result, Err(CreateClientError::TlsError(_))));
+    }
+
+    #[test]
+    fn test_certificate_for_another_host_is_rejected() {
+        let (cert, key, files) = self_signed("other-host");
+
+        let port = mock_router(acceptor(&cert, &key).build());
+        let options = TlsOptions::new().ca_file(&files.cert).domain("other.test");
+        let result = Client::connect_tls(("127.0.0.1", port), &options);
+        assert!(matches!(result, Err(CreateClientError::TlsError(_))));
+
+        let port = mock_router(acceptor(&cert, &key).build());
+        let options = options.accept_invalid_hostnames();
+        let mut client = Client::connect_tls(("127.0.0.1", port), &options).unwrap();
+        assert_eq!(identity(&mut client), "router");
+    }
+
+    #[test]
+    fn test_connect_with_client_certificate() {
+        let (cert, key, files) = self_signed("client");
+        let mut builder = acceptor(&cert, &key);
+        builder.set_ca_file(&files.cert).unwrap();
+        builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        let port = mock_router(builder.build());
+
+        let options = TlsOptions::new()
+            .ca_file(&files.cert)
+            .client_certificate(&files.cert, &files.key);
+        let mut client = Client::connect_tls(("127.0.0.1", port), &options).unwrap();
+
+        assert_eq!(identity(&mut client), "router");
+    }
+
+    #[test]
+    fn test_connect_anonymous() {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        builder.set_cipher_list(ANONYMOUS_CIPHERS).unwrap();
+        builder.set_max_proto_version(Some(SslVersion::TLS1_2)).unwrap();
+        builder.set_tmp_dh(&Dh::get_2048_256().unwrap()).unwrap();
+        let port = mock_router(builder.build());
+
+        let options = TlsOptions::new().anonymous();
+        let mut client = Client::connect_tls(("127.0.0.1", port), &options).unwrap();
+
+        assert_eq!(identity(&mut client), "router");
+    }
+}
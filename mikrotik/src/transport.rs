@@ -0,0 +1,68 @@
+#[cfg(feature = "tls")]
+use openssl::ssl::SslStream;
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+// Transport is the connection a client talks to the router over: a plain TCP socket
+// for the api service or, with the 'tls' feature, a TLS session for api-ssl.
+pub(crate) enum Transport {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(SslStream<TcpStream>),
+}
+
+impl Transport {
+    // Returns the TCP socket the connection runs over.
+    pub(crate) fn socket(&self) -> &TcpStream {
+        match *self {
+            Transport::Plain(ref stream) => stream,
+            #[cfg(feature = "tls")]
+            Transport::Tls(ref stream) => stream.get_ref(),
+        }
+    }
+
+    // Returns a second handle to the connection that can be written to while the
+    // original one is blocked reading.
+    //
+    // TLS sessions keep state shared by both directions, so only plain TCP
+    // connections can be split.
+    pub(crate) fn try_clone(&self) -> Result<TcpStream, io::Error> {
+        match *self {
+            Transport::Plain(ref stream) => stream.try_clone(),
+            #[cfg(feature = "tls")]
+            Transport::Tls(_) => {
+                Err(io::Error::new(io::ErrorKind::Unsupported,
+                                   "TLS connections can not be shared between threads"))
+            }
+        }
+    }
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            Transport::Plain(ref mut stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Transport::Tls(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            Transport::Plain(ref mut stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Transport::Tls(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Transport::Plain(ref mut stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Transport::Tls(ref mut stream) => stream.flush(),
+        }
+    }
+}
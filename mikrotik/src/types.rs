@@ -0,0 +1,687 @@
+use crate::resource::Field;
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+// RosDuration is a time interval in RouterOS notation, used by timeouts, uptimes and
+// lease times.
+//
+// It is parsed from the unit form of RouterOS 7, such as "1w2d3h4m5s" or "500ms",
+// from the clock form of RouterOS 6, such as "1w2d03:04:05", and from a bare number
+// of seconds. It is always written in the unit form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RosDuration(pub Duration);
+
+const DURATION_UNITS: [(&str, u64); 5] = [
+    ("w", 7 * 24 * 3600),
+    ("d", 24 * 3600),
+    ("h", 3600),
+    ("m", 60),
+    ("s", 1),
+];
+
+impl RosDuration {
+    pub fn from_secs(secs: u64) -> RosDuration {
+        RosDuration(Duration::from_secs(secs))
+    }
+
+    pub fn parse(value: &str) -> Option<RosDuration> {
+        if value.is_empty() {
+            return None;
+        }
+
+        let mut total = Duration::ZERO;
+        let mut rest = value;
+
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            if digits == 0 {
+                return None;
+            }
+
+            if rest[digits..].starts_with(':') {
+                total = total.checked_add(parse_clock(rest)?)?;
+                break;
+            }
+
+            let number = rest[..digits].parse::<u64>().ok()?;
+            let tail = &rest[digits..];
+            let unit_len = tail.find(|c: char| c.is_ascii_digit()).unwrap_or(tail.len());
+
+            let part = match &tail[..unit_len] {
+                "ms" => Duration::from_millis(number),
+                "" => Duration::from_secs(number),
+                unit => {
+                    let (_, secs) = DURATION_UNITS.iter().find(|(u, _)| *u == unit)?;
+                    Duration::from_secs(number.checked_mul(*secs)?)
+                }
+            };
+
+            total = total.checked_add(part)?;
+            rest = &tail[unit_len..];
+        }
+
+        Some(RosDuration(total))
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+// Parses the 'hh:mm:ss' form, where the seconds can have a fraction such as
+// "00:00:01.5".
+fn parse_clock(value: &str) -> Option<Duration> {
+    let mut parts = value.split(':');
+    let hours = parts.next()?.parse::<u64>().ok()?;
+    let minutes = parts.next()?.parse::<u64>().ok()?;
+    let seconds = parts.next()?;
+    if parts.next().is_some() || minutes >= 60 {
+        return None;
+    }
+
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let whole = whole.parse::<u64>().ok()?;
+    if whole >= 60 || fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let nanos = format!("{:0<9}", fraction).parse::<u32>().ok()?;
+    let secs = hours.checked_mul(3600)?.checked_add(minutes * 60 + whole)?;
+
+    Some(Duration::new(secs, nanos))
+}
+
+impl fmt::Display for RosDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut secs = self.0.as_secs();
+        let millis = self.0.subsec_millis();
+
+        if secs == 0 && millis == 0 {
+            return f.write_str("0s");
+        }
+
+        for &(unit, len) in DURATION_UNITS.iter() {
+            if secs >= len {
+                write!(f, "{}{}", secs / len, unit)?;
+                secs %= len;
+            }
+        }
+
+        if millis > 0 {
+            write!(f, "{}ms", millis)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Duration> for RosDuration {
+    fn from(duration: Duration) -> RosDuration {
+        RosDuration(duration)
+    }
+}
+
+impl From<RosDuration> for Duration {
+    fn from(duration: RosDuration) -> Duration {
+        duration.0
+    }
+}
+
+impl Field for RosDuration {
+    fn from_value(value: &str) -> Option<RosDuration> {
+        RosDuration::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// RosBool is a boolean property value. The router writes them as "true" and "false",
+// but "yes" and "no" are accepted too, as in the arguments of many commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RosBool(pub bool);
+
+impl RosBool {
+    pub fn parse(value: &str) -> Option<RosBool> {
+        match value {
+            "true" | "yes" => Some(RosBool(true)),
+            "false" | "no" => Some(RosBool(false)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RosBool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(if self.0 { "true" } else { "false" })
+    }
+}
+
+impl From<bool> for RosBool {
+    fn from(value: bool) -> RosBool {
+        RosBool(value)
+    }
+}
+
+impl From<RosBool> for bool {
+    fn from(value: RosBool) -> bool {
+        value.0
+    }
+}
+
+impl Field for RosBool {
+    fn from_value(value: &str) -> Option<RosBool> {
+        RosBool::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+// RosDate is a calendar date, written by the router as "jan/02/2024". RouterOS 7.10
+// and later write "2024-01-02" instead, which is parsed as well.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RosDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl RosDate {
+    pub fn parse(value: &str) -> Option<RosDate> {
+        let (year, month, day) = match value.split_once('/') {
+            Some((month, rest)) => {
+                let (day, year) = rest.split_once('/')?;
+                let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))? + 1;
+                (year, month as u8, day.parse().ok()?)
+            }
+            None => {
+                let mut parts = value.splitn(3, '-');
+                let year = parts.next()?;
+                (year, parts.next()?.parse().ok()?, parts.next()?.parse().ok()?)
+            }
+        };
+
+        let date = RosDate { year: year.parse().ok()?, month, day };
+        if month == 0 || month > 12 || day == 0 || day > date.days_in_month() {
+            return None;
+        }
+
+        Some(date)
+    }
+
+    fn days_in_month(&self) -> u8 {
+        match self.month {
+            2 if is_leap_year(self.year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+impl fmt::Display for RosDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let month = MONTHS[usize::from(self.month.clamp(1, 12)) - 1];
+        write!(f, "{}/{:02}/{:04}", month, self.day, self.year)
+    }
+}
+
+impl Field for RosDate {
+    fn from_value(value: &str) -> Option<RosDate> {
+        RosDate::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// RosTime is a time of day, written by the router as "10:00:00".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RosTime {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl RosTime {
+    pub fn parse(value: &str) -> Option<RosTime> {
+        let mut parts = value.split(':');
+        let time = RosTime {
+            hour: parts.next()?.parse().ok()?,
+            minute: parts.next()?.parse().ok()?,
+            second: parts.next()?.parse().ok()?,
+        };
+
+        if parts.next().is_some() || time.hour > 23 || time.minute > 59 || time.second > 59 {
+            return None;
+        }
+
+        Some(time)
+    }
+
+    // Returns the time elapsed since midnight.
+    pub fn since_midnight(&self) -> Duration {
+        let secs = u64::from(self.hour) * 3600 + u64::from(self.minute) * 60;
+        Duration::from_secs(secs + u64::from(self.second))
+    }
+}
+
+impl fmt::Display for RosTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+impl Field for RosTime {
+    fn from_value(value: &str) -> Option<RosTime> {
+        RosTime::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// RosDateTime is a timestamp such as "jan/02/2024 10:00:00", in the time zone of the
+// router.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RosDateTime {
+    pub date: RosDate,
+    pub time: RosTime,
+}
+
+impl RosDateTime {
+    pub fn parse(value: &str) -> Option<RosDateTime> {
+        let (date, time) = value.split_once(' ')?;
+
+        Some(RosDateTime {
+            date: RosDate::parse(date)?,
+            time: RosTime::parse(time)?,
+        })
+    }
+}
+
+impl fmt::Display for RosDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+impl Field for RosDateTime {
+    fn from_value(value: &str) -> Option<RosDateTime> {
+        RosDateTime::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// IpPrefix is an IPv4 or IPv6 address with the length of its network prefix, such as
+// "192.168.88.1/24" or "2001:db8::/32". A bare address is parsed as a prefix of its
+// full length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IpPrefix {
+    pub address: IpAddr,
+    pub len: u8,
+}
+
+impl IpPrefix {
+    // Returns None if 'len' is longer than the address.
+    pub fn new(address: IpAddr, len: u8) -> Option<IpPrefix> {
+        if len > max_prefix_len(&address) {
+            return None;
+        }
+
+        Some(IpPrefix { address, len })
+    }
+
+    pub fn parse(value: &str) -> Option<IpPrefix> {
+        match value.split_once('/') {
+            Some((address, len)) => {
+                if !len.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                IpPrefix::new(address.parse().ok()?, len.parse().ok()?)
+            }
+            None => {
+                let address = value.parse().ok()?;
+                Some(IpPrefix { address, len: max_prefix_len(&address) })
+            }
+        }
+    }
+
+    // Returns the first address of the prefix, such as 192.168.88.0 for
+    // 192.168.88.1/24.
+    pub fn network(&self) -> IpAddr {
+        match self.address {
+            IpAddr::V4(a) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.len)).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(a) & mask))
+            }
+            IpAddr::V6(a) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.len)).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(a) & mask))
+            }
+        }
+    }
+
+    // Returns true if 'address' is in the network of the prefix.
+    pub fn contains(&self, address: IpAddr) -> bool {
+        match IpPrefix::new(address, self.len) {
+            Some(other) => other.network() == self.network(),
+            None => false,
+        }
+    }
+}
+
+fn max_prefix_len(address: &IpAddr) -> u8 {
+    if address.is_ipv4() { 32 } else { 128 }
+}
+
+// The default prefix is 0.0.0.0/0, the default route.
+impl Default for IpPrefix {
+    fn default() -> IpPrefix {
+        IpPrefix { address: IpAddr::V4(Ipv4Addr::UNSPECIFIED), len: 0 }
+    }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.len)
+    }
+}
+
+impl Field for IpPrefix {
+    fn from_value(value: &str) -> Option<IpPrefix> {
+        IpPrefix::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl Field for IpAddr {
+    fn from_value(value: &str) -> Option<IpAddr> {
+        value.parse().ok()
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+// ListAddress is the address of a firewall address-list entry: a single address, a
+// prefix such as "10.0.0.0/8", a range such as "10.0.0.1-10.0.0.20" or a DNS name the
+// router resolves and keeps updated.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ListAddress {
+    Address(IpAddr),
+    Prefix(IpPrefix),
+    Range(IpAddr, IpAddr),
+    Hostname(String),
+}
+
+impl ListAddress {
+    pub fn parse(value: &str) -> Option<ListAddress> {
+        if value.is_empty() {
+            return None;
+        }
+        if let Ok(address) = value.parse() {
+            return Some(ListAddress::Address(address));
+        }
+        if value.contains('/') {
+            return IpPrefix::parse(value).map(ListAddress::Prefix);
+        }
+        if let Some((first, last)) = value.split_once('-') {
+            if let (Ok(first), Ok(last)) = (first.parse(), last.parse()) {
+                return Some(ListAddress::Range(first, last));
+            }
+        }
+
+        Some(ListAddress::Hostname(value.to_string()))
+    }
+}
+
+// The default address is an empty hostname, which is not sent.
+impl Default for ListAddress {
+    fn default() -> ListAddress {
+        ListAddress::Hostname(String::new())
+    }
+}
+
+impl fmt::Display for ListAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ListAddress::Address(ref a) => a.fmt(f),
+            ListAddress::Prefix(ref p) => p.fmt(f),
+            ListAddress::Range(ref first, ref last) => write!(f, "{}-{}", first, last),
+            ListAddress::Hostname(ref name) => name.fmt(f),
+        }
+    }
+}
+
+impl From<IpAddr> for ListAddress {
+    fn from(address: IpAddr) -> ListAddress {
+        ListAddress::Address(address)
+    }
+}
+
+impl From<IpPrefix> for ListAddress {
+    fn from(prefix: IpPrefix) -> ListAddress {
+        ListAddress::Prefix(prefix)
+    }
+}
+
+impl Field for ListAddress {
+    fn from_value(value: &str) -> Option<ListAddress> {
+        ListAddress::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        match *self {
+            ListAddress::Hostname(ref name) if name.is_empty() => None,
+            _ => Some(self.to_string()),
+        }
+    }
+}
+
+// MacAddress is an Ethernet address, written by the router as "4C:5E:0C:11:22:33".
+// Addresses separated with '-' are parsed as well.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    pub fn parse(value: &str) -> Option<MacAddress> {
+        let mut bytes = [0; 6];
+        let mut parts = value.split([':', '-']);
+
+        for byte in bytes.iter_mut() {
+            let part = parts.next()?;
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            *byte = u8::from_str_radix(part, 16).ok()?;
+        }
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(MacAddress(bytes))
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let b = &self.0;
+        write!(f, "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}", b[0], b[1], b[2], b[3], b[4], b[5])
+    }
+}
+
+impl Field for MacAddress {
+    fn from_value(value: &str) -> Option<MacAddress> {
+        MacAddress::parse(value)
+    }
+
+    fn to_value(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(value: &str) -> String {
+        RosDuration::parse(value).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let secs = |s| Some(RosDuration::from_secs(s));
+
+        assert_eq!(RosDuration::parse("5m30s"), secs(330));
+        assert_eq!(RosDuration::parse("1w2d3h4m5s"), secs(788_645));
+        assert_eq!(RosDuration::parse("1w2d03:04:05"), secs(788_645));
+        assert_eq!(RosDuration::parse("00:00:10"), secs(10));
+        assert_eq!(RosDuration::parse("1d"), secs(86_400));
+        assert_eq!(RosDuration::parse("30"), secs(30));
+        assert_eq!(RosDuration::parse("0s"), secs(0));
+        assert_eq!(RosDuration::parse("1s500ms").unwrap().as_duration(),
+                   Duration::from_millis(1500));
+        assert_eq!(RosDuration::parse("00:00:01.25").unwrap().as_duration(),
+                   Duration::from_millis(1250));
+    }
+
+    #[test]
+    fn test_parse_invalid_duration() {
+        for value in &["", "s", "5x", "1h-2m", "01:60:00", "1:2", "1:2:3:4", "5m 30s"] {
+            assert_eq!(RosDuration::parse(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        assert_eq!(round_trip("1w2d3h4m5s"), "1w2d3h4m5s");
+        assert_eq!(round_trip("1w2d03:04:05"), "1w2d3h4m5s");
+        assert_eq!(round_trip("90m"), "1h30m");
+        assert_eq!(round_trip("0s"), "0s");
+        assert_eq!(round_trip("2s500ms"), "2s500ms");
+        assert_eq!(round_trip("1d"), "1d");
+
+        let duration = Duration::from_secs(3 * 86_400 + 61);
+        assert_eq!(Duration::from(RosDuration::from(duration)), duration);
+        assert_eq!(RosDuration::parse(&RosDuration::from(duration).to_string()).unwrap().0,
+                   duration);
+    }
+
+    #[test]
+    fn test_bool() {
+        assert_eq!(RosBool::parse("yes"), Some(RosBool(true)));
+        assert_eq!(RosBool::parse("true"), Some(RosBool(true)));
+        assert_eq!(RosBool::parse("no"), Some(RosBool(false)));
+        assert_eq!(RosBool::parse("false"), Some(RosBool(false)));
+        assert_eq!(RosBool::parse("True"), None);
+        assert_eq!(RosBool::parse(&RosBool(true).to_string()), Some(RosBool(true)));
+        assert_eq!(RosBool::parse(&RosBool(false).to_string()), Some(RosBool(false)));
+        assert!(bool::from(RosBool::from(true)));
+    }
+
+    #[test]
+    fn test_date_time() {
+        let timestamp = RosDateTime::parse("jan/02/2024 10:00:00").unwrap();
+
+        assert_eq!(timestamp.date, RosDate { year: 2024, month: 1, day: 2 });
+        assert_eq!(timestamp.time, RosTime { hour: 10, minute: 0, second: 0 });
+        assert_eq!(timestamp.to_string(), "jan/02/2024 10:00:00");
+        assert_eq!(timestamp.time.since_midnight(), Duration::from_secs(36_000));
+        assert_eq!(RosDateTime::parse("2024-01-02 10:00:00"), Some(timestamp));
+        assert_eq!(RosDate::parse("Dec/31/1999").unwrap().to_string(), "dec/31/1999");
+        assert!(RosDate::parse("feb/29/2024").is_some());
+        assert!(RosDateTime::parse("jan/02/2024 09:59:59").unwrap() < timestamp);
+    }
+
+    #[test]
+    fn test_invalid_date_time() {
+        assert_eq!(RosDate::parse("feb/29/2023"), None);
+        assert_eq!(RosDate::parse("foo/01/2024"), None);
+        assert_eq!(RosDate::parse("2024-13-01"), None);
+        assert_eq!(RosTime::parse("24:00:00"), None);
+        assert_eq!(RosTime::parse("10:00"), None);
+        assert_eq!(RosDateTime::parse("jan/02/2024"), None);
+    }
+
+    #[test]
+    fn test_ip_prefix() {
+        let prefix = IpPrefix::parse("192.168.88.1/24").unwrap();
+
+        assert_eq!(prefix.address, "192.168.88.1".parse::<IpAddr>().unwrap());
+        assert_eq!(prefix.len, 24);
+        assert_eq!(prefix.to_string(), "192.168.88.1/24");
+        assert_eq!(prefix.network().to_string(), "192.168.88.0");
+        assert!(prefix.contains("192.168.88.200".parse().unwrap()));
+        assert!(!prefix.contains("192.168.89.1".parse().unwrap()));
+        assert!(!prefix.contains("::1".parse().unwrap()));
+
+        let prefix = IpPrefix::parse("2001:db8::1/32").unwrap();
+        assert_eq!(prefix.network().to_string(), "2001:db8::");
+        assert_eq!(prefix.to_string(), "2001:db8::1/32");
+
+        assert_eq!(IpPrefix::parse("10.0.0.1").unwrap().to_string(), "10.0.0.1/32");
+        assert_eq!(IpPrefix::parse("0.0.0.0/0"), Some(IpPrefix::default()));
+        assert_eq!(IpPrefix::default().network().to_string(), "0.0.0.0");
+        assert_eq!(IpPrefix::parse("10.0.0.0/33"), None);
+        assert_eq!(IpPrefix::parse("10.0.0.0/+8"), None);
+        assert_eq!(IpPrefix::parse("ether1"), None);
+    }
+
+    #[test]
+    fn test_list_address() {
+        let cases = ["10.0.0.1", "2001:db8::1", "10.0.0.0/8", "10.0.0.1-10.0.0.20", "example.com",
+                     "my-router.example.com"];
+        for case in cases.iter() {
+            assert_eq!(ListAddress::parse(case).unwrap().to_string(), *case);
+        }
+
+        assert_eq!(ListAddress::parse("10.0.0.1"),
+                   Some(ListAddress::Address("10.0.0.1".parse().unwrap())));
+        assert_eq!(ListAddress::parse("10.0.0.0/8"), IpPrefix::parse("10.0.0.0/8").map(From::from));
+        assert_eq!(ListAddress::parse("10.0.0.1-10.0.0.20"),
+                   Some(ListAddress::Range("10.0.0.1".parse().unwrap(),
+                                           "10.0.0.20".parse().unwrap())));
+        assert_eq!(ListAddress::parse("example.com"),
+                   Some(ListAddress::Hostname(String::from("example.com"))));
+        assert_eq!(ListAddress::parse("10.0.0.0/33"), None);
+        assert_eq!(ListAddress::parse(""), None);
+        assert_eq!(ListAddress::default().to_value(), None);
+    }
+
+    #[test]
+    fn test_mac_address() {
+        let mac = MacAddress::parse("4c:5e:0c:11:22:3a").unwrap();
+
+        assert_eq!(mac, MacAddress([0x4C, 0x5E, 0x0C, 0x11, 0x22, 0x3A]));
+        assert_eq!(mac.to_string(), "4C:5E:0C:11:22:3A");
+        assert_eq!(MacAddress::parse("4C-5E-0C-11-22-3A"), Some(mac));
+        assert_eq!(MacAddress::parse("4C:5E:0C:11:22"), None);
+        assert_eq!(MacAddress::parse("4C:5E:0C:11:22:3A:00"), None);
+        assert_eq!(MacAddress::parse("4C:5E:0C:11:22:+3"), None);
+        assert_eq!(MacAddress::parse(""), None);
+    }
+}
@@ -1,4 +1,4 @@
-use errors::UnhexlifyError;
+use crate::errors::UnhexlifyError;
 
 
 // Returns the binary data represented by the hexadecimal string 'hexstr'. 'hexstr'
@@ -14,9 +14,9 @@ use errors::UnhexlifyError;
 // Python 3 binascii.unhexlify
 //     https://docs.python.org/3/library/binascii.html#binascii.unhexlify
 //
-pub fn unhexlify<'a>(hexstr: &str) -> Result<Vec<u8>, UnhexlifyError> {
+pub fn unhexlify(hexstr: &str) -> Result<Vec<u8>, UnhexlifyError> {
     let string_len = hexstr.len();
-    if string_len == 0 || string_len % 2 != 0 {
+    if string_len == 0 || !string_len.is_multiple_of(2) {
         return Err(UnhexlifyError::OddCharacterCount(string_len));
     }
 
@@ -26,10 +26,10 @@ pub fn unhexlify<'a>(hexstr: &str) -> Result<Vec<u8>, UnhexlifyError> {
 
     while i < chars.len() - 1 {
         let mut c = chars[i];
-        let top = try!(c.to_digit(16).ok_or(UnhexlifyError::InvalidHexDigit(c))) as u8;
+        let top = c.to_digit(16).ok_or(UnhexlifyError::InvalidHexDigit(c))? as u8;
 
         c = chars[i + 1];
-        let bottom = try!(c.to_digit(16).ok_or(UnhexlifyError::InvalidHexDigit(c))) as u8;
+        let bottom = c.to_digit(16).ok_or(UnhexlifyError::InvalidHexDigit(c))? as u8;
 
         let parsed_num = (top << 4) + bottom;
         result.push(parsed_num);
@@ -43,7 +43,7 @@ pub fn unhexlify<'a>(hexstr: &str) -> Result<Vec<u8>, UnhexlifyError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use errors::UnhexlifyError;
+    use crate::errors::UnhexlifyError;
 
     #[test]
     fn test_valid_hex_string() {
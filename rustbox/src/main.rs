@@ -1,5 +1,7 @@
 extern crate mikrotik;
 
+use mikrotik::Executor;
+
 use std::env;
 
 
@@ -18,16 +20,18 @@ use std::env;
 fn main() {
     let mut args = env::args();
     let ip = args.nth(1).expect("No server ip specified!");
-    let user = args.nth(0).expect("No user specified!");
-    let pw = args.nth(0).expect("No password specified!");
-    let port = args.nth(0).unwrap_or(String::new());
+    let user = args.next().expect("No user specified!");
+    let pw = args.next().expect("No password specified!");
+    let port = args.next()
+        .map(|p| p.parse().expect("Invalid port!"))
+        .unwrap_or(mikrotik::client::DEFAULT_PORT);
 
-    let mut mtclient = mikrotik::Client::connect(&ip, &port).unwrap();
+    let mut mtclient = mikrotik::Client::connect((ip.as_str(), port)).unwrap();
     println!("Connected!");
 
     println!["Performing login..."];
     mtclient.login(&user, &pw).unwrap();
-    mtclient.print();
+    println!("{}", mtclient.info());
     println!("Connected!!");
 
     // let addresses = mtclient.get_address_list().unwrap();